@@ -0,0 +1,35 @@
+// The `key = value` files the days read their settings from. `#` starts a comment and blank
+// lines are skipped, `set` gets every pair in order and its errors get the line number.
+pub fn parse_key_values(config: &str, mut set: impl FnMut(&str, &str) -> Result<(), String>) -> Result<(), String> {
+    for (idx, line) in config.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected key = value", idx + 1));
+        };
+        set(key.trim(), value.trim()).map_err(|message| format!("line {}: {}", idx + 1, message))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_values() {
+        let mut pairs = Vec::new();
+        let config = "# settings\nmax = 5 # inline\n\n  name=a b  \n";
+        parse_key_values(config, |key, value| {
+            pairs.push((key.to_string(), value.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(pairs, vec![("max".to_string(), "5".to_string()), ("name".to_string(), "a b".to_string())]);
+
+        assert_eq!(parse_key_values("a = 1\nb", |_, _| Ok(())), Err("line 2: expected key = value".to_string()));
+        assert_eq!(parse_key_values("\n\nc = 1", |key, _| Err(format!("unknown: {}", key))), Err("line 3: unknown: c".to_string()));
+    }
+}
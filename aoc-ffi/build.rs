@@ -0,0 +1,15 @@
+use std::env;
+use std::path::PathBuf;
+
+// The header goes to OUT_DIR, tests/header.rs checks include/aoc.h against it.
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    cbindgen::generate(&crate_dir)
+        .expect("could not generate C bindings")
+        .write_to_file(out_dir.join("aoc.h"));
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
@@ -0,0 +1,159 @@
+#ifndef AOC_H
+#define AOC_H
+
+/* Generated by cbindgen from aoc-ffi/src/lib.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result of every `aoc_*` call. The answer is only written on `AOC_STATUS_OK`.
+ */
+typedef enum AocStatus {
+  AOC_STATUS_OK = 0,
+  AOC_STATUS_UNKNOWN_DAY = 1,
+  AOC_STATUS_UNKNOWN_PART = 2,
+  /**
+   * The input is not UTF-8 or the solver could not parse it.
+   */
+  AOC_STATUS_INVALID_INPUT = 3,
+  AOC_STATUS_NULL_POINTER = 4,
+} AocStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Solves `part` (1 or 2) of `day` for the `len` bytes of puzzle input at `input`.
+ *
+ * # Safety
+ *
+ * `input` must point to `len` readable bytes and `answer` to a writable `uint64_t`.
+ */
+enum AocStatus aoc_solve(uint8_t day,
+                         uint8_t part,
+                         const uint8_t *input,
+                         size_t len,
+                         uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day1_part_one(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day1_part_two(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day2_part_one(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day2_part_two(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day3_part_one(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day3_part_two(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day4_part_one(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day4_part_two(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day5_part_one(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day5_part_two(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day6_part_one(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day6_part_two(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day7_part_one(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day7_part_two(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day8_part_one(const uint8_t *input, size_t len, uint64_t *answer);
+
+/**
+ * # Safety
+ *
+ * See `aoc_solve`.
+ */
+enum AocStatus aoc_day8_part_two(const uint8_t *input, size_t len, uint64_t *answer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AOC_H */
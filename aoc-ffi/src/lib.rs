@@ -0,0 +1,211 @@
+use std::slice;
+
+use aoc::SolveError;
+
+// Doc comments in this file end up in include/aoc.h, which is generated with cbindgen
+// by build.rs and checked against the copy in the tree by tests/header.rs.
+
+/// Result of every `aoc_*` call. The answer is only written on `AOC_STATUS_OK`.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum AocStatus {
+    Ok = 0,
+    UnknownDay = 1,
+    UnknownPart = 2,
+    /// The input is not UTF-8 or the solver could not parse it.
+    InvalidInput = 3,
+    NullPointer = 4,
+}
+
+fn solve(day: u8, part: u8, input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    if input.is_null() || answer.is_null() {
+        return AocStatus::NullPointer;
+    }
+
+    // SAFETY: the caller guarantees `input` points to `len` readable bytes
+    let bytes = unsafe { slice::from_raw_parts(input, len) };
+    let Ok(input) = std::str::from_utf8(bytes) else {
+        return AocStatus::InvalidInput;
+    };
+
+    match aoc::solve(day, part, input) {
+        Ok(result) => {
+            // SAFETY: checked for null above, the caller guarantees it is writable
+            unsafe { *answer = result };
+            AocStatus::Ok
+        }
+        Err(SolveError::UnknownDay(_)) => AocStatus::UnknownDay,
+        Err(SolveError::UnknownPart(_)) => AocStatus::UnknownPart,
+        Err(_) => AocStatus::InvalidInput,
+    }
+}
+
+/// Solves `part` (1 or 2) of `day` for the `len` bytes of puzzle input at `input`.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes and `answer` to a writable `uint64_t`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(day: u8, part: u8, input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(day, part, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day1_part_one(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(1, 1, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day1_part_two(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(1, 2, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day2_part_one(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(2, 1, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day2_part_two(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(2, 2, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day3_part_one(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(3, 1, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day3_part_two(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(3, 2, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day4_part_one(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(4, 1, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day4_part_two(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(4, 2, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day5_part_one(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(5, 1, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day5_part_two(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(5, 2, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day6_part_one(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(6, 1, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day6_part_two(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(6, 2, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day7_part_one(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(7, 1, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day7_part_two(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(7, 2, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day8_part_one(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(8, 1, input, len, answer)
+}
+
+/// # Safety
+///
+/// See `aoc_solve`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_day8_part_two(input: *const u8, len: usize, answer: *mut u64) -> AocStatus {
+    solve(8, 2, input, len, answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+    #[test]
+    fn test_solve() {
+        let mut answer = 0;
+        let status = unsafe { aoc_day1_part_two(INPUT.as_ptr(), INPUT.len(), &mut answer) };
+        assert_eq!(status, AocStatus::Ok);
+        assert_eq!(answer, 31);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut answer = 7;
+        let invalid = [0xff, 0xfe];
+        assert_eq!(unsafe { aoc_solve(1, 1, invalid.as_ptr(), invalid.len(), &mut answer) }, AocStatus::InvalidInput);
+        assert_eq!(unsafe { aoc_solve(1, 1, "3 x".as_ptr(), 3, &mut answer) }, AocStatus::InvalidInput);
+        assert_eq!(unsafe { aoc_solve(26, 1, INPUT.as_ptr(), INPUT.len(), &mut answer) }, AocStatus::UnknownDay);
+        assert_eq!(unsafe { aoc_solve(1, 3, INPUT.as_ptr(), INPUT.len(), &mut answer) }, AocStatus::UnknownPart);
+        assert_eq!(unsafe { aoc_solve(1, 1, std::ptr::null(), 0, &mut answer) }, AocStatus::NullPointer);
+        assert_eq!(answer, 7);
+    }
+}
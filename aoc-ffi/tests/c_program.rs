@@ -0,0 +1,33 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+// Compiles tests/smoke.c against the generated header and the cdylib cargo built alongside
+// this test, then runs it. The C program exits non-zero on any mismatch.
+#[test]
+fn test_c_program() {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let out_dir = tempfile::tempdir().unwrap();
+    let program = out_dir.path().join("smoke");
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(crate_dir.join("tests").join("smoke.c"))
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg("-laoc_ffi")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("could not run the C compiler");
+    assert!(status.success());
+
+    let output = Command::new(&program)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .env("DYLD_LIBRARY_PATH", lib_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
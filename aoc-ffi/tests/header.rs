@@ -0,0 +1,20 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+// include/aoc.h is checked in so C users don't need cbindgen. Run with AOC_UPDATE_HEADER=1 to
+// replace it with the one build.rs generated.
+#[test]
+fn test_header_is_current() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/aoc.h"));
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("include").join("aoc.h");
+
+    if env::var_os("AOC_UPDATE_HEADER").is_some() {
+        fs::write(&path, generated).unwrap();
+    }
+    let checked_in = fs::read_to_string(&path).unwrap();
+    assert!(
+        checked_in == generated,
+        "include/aoc.h is out of date, run `AOC_UPDATE_HEADER=1 cargo test -p aoc-ffi --test header`"
+    );
+}
@@ -0,0 +1,37 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc.h"
+
+static const char *DAY1 = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+static const char *DAY3 = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+static int check(const char *name, AocStatus status, AocStatus expected_status, uint64_t answer, uint64_t expected) {
+    if (status != expected_status || (status == AOC_STATUS_OK && answer != expected)) {
+        fprintf(stderr, "%s: status %d answer %llu\n", name, (int)status, (unsigned long long)answer);
+        return 1;
+    }
+    return 0;
+}
+
+int main(void) {
+    int failures = 0;
+    uint64_t answer = 0;
+
+    AocStatus status = aoc_day1_part_one((const uint8_t *)DAY1, strlen(DAY1), &answer);
+    failures += check("day1 part one", status, AOC_STATUS_OK, answer, 11);
+
+    status = aoc_day1_part_two((const uint8_t *)DAY1, strlen(DAY1), &answer);
+    failures += check("day1 part two", status, AOC_STATUS_OK, answer, 31);
+
+    status = aoc_solve(3, 2, (const uint8_t *)DAY3, strlen(DAY3), &answer);
+    failures += check("day3 part two", status, AOC_STATUS_OK, answer, 48);
+
+    status = aoc_day1_part_one((const uint8_t *)"3 x", 3, &answer);
+    failures += check("invalid input", status, AOC_STATUS_INVALID_INPUT, answer, 0);
+
+    status = aoc_solve(26, 1, (const uint8_t *)DAY1, strlen(DAY1), &answer);
+    failures += check("unknown day", status, AOC_STATUS_UNKNOWN_DAY, answer, 0);
+
+    return failures;
+}
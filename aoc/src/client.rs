@@ -0,0 +1,373 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+const USER_AGENT: &str = "github.com/Kylea650/advent-of-code-2024";
+
+// in the inputs directory, so separate runs of `aoc fetch` and `aoc submit` share the throttle
+const LAST_REQUEST_FILE: &str = ".last-request";
+
+#[derive(Debug)]
+pub enum ClientError {
+    MissingSession,
+    Io(io::Error),
+    Http(ureq::Error),
+    Status(u16),
+    UnexpectedResponse(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::MissingSession => {
+                write!(f, "no session token, set {} or provide a session file", SESSION_ENV)
+            }
+            ClientError::Io(err) => write!(f, "{}", err),
+            ClientError::Http(err) => write!(f, "request failed: {}", err),
+            ClientError::Status(status) => write!(f, "server responded with status {}", status),
+            ClientError::UnexpectedResponse(body) => write!(f, "could not understand response: {}", body),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<io::Error> for ClientError {
+    fn from(err: io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
+impl From<ureq::Error> for ClientError {
+    fn from(err: ureq::Error) -> Self {
+        ClientError::Http(err)
+    }
+}
+
+// The session cookie comes from the environment first, then from the given file.
+pub fn load_session(file: &Path) -> Result<String, ClientError> {
+    if let Ok(session) = std::env::var(SESSION_ENV) {
+        if !session.trim().is_empty() {
+            return Ok(session.trim().to_string());
+        }
+    }
+    match fs::read_to_string(file) {
+        Ok(session) if !session.trim().is_empty() => Ok(session.trim().to_string()),
+        Ok(_) => Err(ClientError::MissingSession),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Err(ClientError::MissingSession),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Submission {
+    Accepted,
+    Rejected(Option<Hint>),
+    RateLimited(Option<Duration>),
+    AlreadySolved,
+}
+
+impl fmt::Display for Submission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Submission::Accepted => write!(f, "accepted"),
+            Submission::Rejected(Some(Hint::TooHigh)) => write!(f, "rejected, answer is too high"),
+            Submission::Rejected(Some(Hint::TooLow)) => write!(f, "rejected, answer is too low"),
+            Submission::Rejected(None) => write!(f, "rejected"),
+            Submission::RateLimited(Some(wait)) => write!(f, "rate limited, wait {}s", wait.as_secs()),
+            Submission::RateLimited(None) => write!(f, "rate limited"),
+            Submission::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+// the answer page is plain HTML, we only look for the sentences that carry the verdict
+fn parse_submission(body: &str) -> Result<Submission, ClientError> {
+    if body.contains("That's the right answer") {
+        Ok(Submission::Accepted)
+    } else if body.contains("That's not the right answer") {
+        let hint = if body.contains("too high") {
+            Some(Hint::TooHigh)
+        } else if body.contains("too low") {
+            Some(Hint::TooLow)
+        } else {
+            None
+        };
+        Ok(Submission::Rejected(hint))
+    } else if body.contains("You gave an answer too recently") {
+        Ok(Submission::RateLimited(parse_wait(body)))
+    } else if body.contains("You don't seem to be solving the right level") {
+        Ok(Submission::AlreadySolved)
+    } else {
+        Err(ClientError::UnexpectedResponse(body.chars().take(200).collect()))
+    }
+}
+
+// "You have 1m 2s left to wait." or "You have 38s left to wait."
+fn parse_wait(body: &str) -> Option<Duration> {
+    let start = body.find("You have ")? + "You have ".len();
+    let end = start + body[start..].find(" left to wait")?;
+
+    body[start..end]
+        .split_ascii_whitespace()
+        .try_fold(0, |acc, part| {
+            if let Some(minutes) = part.strip_suffix('m') {
+                Some(acc + minutes.parse::<u64>().ok()? * 60)
+            } else {
+                Some(acc + part.strip_suffix('s')?.parse::<u64>().ok()?)
+            }
+        })
+        .map(Duration::from_secs)
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub base_url: String,
+    pub year: u16,
+    pub inputs_dir: PathBuf,
+    pub min_interval: Duration,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            base_url: "https://adventofcode.com".to_string(),
+            year: 2024,
+            inputs_dir: PathBuf::from("inputs"),
+            min_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+pub struct Client {
+    agent: ureq::Agent,
+    session: String,
+    config: ClientConfig,
+    last_request: Option<SystemTime>,
+}
+
+impl Client {
+    pub fn new(session: String, config: ClientConfig) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(USER_AGENT)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+        // a missing or unreadable file just means no request was made yet
+        let last_request = fs::read_to_string(config.inputs_dir.join(LAST_REQUEST_FILE))
+            .ok()
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+        Client { agent, session, config, last_request }
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.config.inputs_dir.join(format!("day{}.txt", day))
+    }
+
+    // Returns the cached input when there is one, otherwise downloads and caches it.
+    pub fn fetch_input(&mut self, day: u8) -> Result<String, ClientError> {
+        let path = self.input_path(day);
+        if path.exists() {
+            return Ok(fs::read_to_string(path)?);
+        }
+
+        self.throttle()?;
+        let url = format!("{}/{}/day/{}/input", self.config.base_url, self.config.year, day);
+        let mut response = self
+            .agent
+            .get(&url)
+            .header("Cookie", format!("session={}", self.session))
+            .call()?;
+        if response.status() != 200 {
+            return Err(ClientError::Status(response.status().as_u16()));
+        }
+        let input = response.body_mut().read_to_string()?;
+
+        fs::create_dir_all(&self.config.inputs_dir)?;
+        fs::write(path, &input)?;
+        Ok(input)
+    }
+
+    pub fn submit(&mut self, day: u8, part: u8, answer: &str) -> Result<Submission, ClientError> {
+        self.throttle()?;
+        let url = format!("{}/{}/day/{}/answer", self.config.base_url, self.config.year, day);
+        let mut response = self
+            .agent
+            .post(&url)
+            .header("Cookie", format!("session={}", self.session))
+            .send_form([("level", part.to_string()), ("answer", answer.to_string())])?;
+        if response.status() != 200 {
+            return Err(ClientError::Status(response.status().as_u16()));
+        }
+        parse_submission(&response.body_mut().read_to_string()?)
+    }
+
+    fn throttle(&mut self) -> Result<(), ClientError> {
+        if let Some(last_request) = self.last_request {
+            // a clock that went backwards counts as no time having passed
+            let elapsed = last_request.elapsed().unwrap_or(Duration::ZERO);
+            if elapsed < self.config.min_interval {
+                thread::sleep(self.config.min_interval - elapsed);
+            }
+        }
+        let now = SystemTime::now();
+        self.last_request = Some(now);
+
+        let millis = now.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_millis();
+        fs::create_dir_all(&self.config.inputs_dir)?;
+        fs::write(self.config.inputs_dir.join(LAST_REQUEST_FILE), millis.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{self, Receiver};
+    use std::time::Instant;
+    use tiny_http::{Response, Server};
+
+    struct Recorded {
+        method: String,
+        url: String,
+        cookie: String,
+        body: String,
+    }
+
+    // serves `responses` in order and records every request it sees
+    fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, Receiver<Recorded>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let mut request = server.recv().unwrap();
+                let cookie = request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv("Cookie"))
+                    .map(|header| header.value.to_string())
+                    .unwrap_or_default();
+                let mut request_body = String::new();
+                request.as_reader().read_to_string(&mut request_body).unwrap();
+                sender
+                    .send(Recorded {
+                        method: request.method().to_string(),
+                        url: request.url().to_string(),
+                        cookie,
+                        body: request_body,
+                    })
+                    .unwrap();
+                request.respond(Response::from_string(body).with_status_code(status)).unwrap();
+            }
+        });
+        (url, receiver)
+    }
+
+    fn client(base_url: String, inputs_dir: &Path) -> Client {
+        let config = ClientConfig {
+            base_url,
+            inputs_dir: inputs_dir.to_path_buf(),
+            min_interval: Duration::ZERO,
+            ..ClientConfig::default()
+        };
+        Client::new("abc123".to_string(), config)
+    }
+
+    #[test]
+    fn test_fetch_input_caches() {
+        let (url, requests) = mock_server(vec![(200, "3   4\n4   3\n")]);
+        let inputs = tempfile::tempdir().unwrap();
+        let mut client = client(url, inputs.path());
+
+        assert_eq!(client.fetch_input(1).unwrap(), "3   4\n4   3\n");
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.url, "/2024/day/1/input");
+        assert_eq!(request.cookie, "session=abc123");
+        assert_eq!(fs::read_to_string(inputs.path().join("day1.txt")).unwrap(), "3   4\n4   3\n");
+
+        // the mock only answers once, so a second download would fail instead of reading the cache
+        assert_eq!(client.fetch_input(1).unwrap(), "3   4\n4   3\n");
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_fetch_input_error() {
+        let (url, _requests) = mock_server(vec![(400, "Please log in to get your puzzle input.")]);
+        let inputs = tempfile::tempdir().unwrap();
+        let mut client = client(url, inputs.path());
+
+        assert!(matches!(client.fetch_input(2), Err(ClientError::Status(400))));
+        assert!(!inputs.path().join("day2.txt").exists());
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, requests) = mock_server(vec![
+            (200, "<article><p>That's the right answer!  You are one gold star closer.</p></article>"),
+            (200, "<article><p>That's not the right answer; your answer is too low.</p></article>"),
+            (200, "<article><p>You gave an answer too recently.  You have 1m 2s left to wait.</p></article>"),
+            (200, "<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>"),
+            (200, "<html>maintenance</html>"),
+        ]);
+        let inputs = tempfile::tempdir().unwrap();
+        let mut client = client(url, inputs.path());
+
+        assert_eq!(client.submit(1, 2, "31").unwrap(), Submission::Accepted);
+        let request = requests.recv().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "/2024/day/1/answer");
+        assert_eq!(request.body, "level=2&answer=31");
+
+        assert_eq!(client.submit(1, 2, "30").unwrap(), Submission::Rejected(Some(Hint::TooLow)));
+        assert_eq!(
+            client.submit(1, 2, "32").unwrap(),
+            Submission::RateLimited(Some(Duration::from_secs(62)))
+        );
+        assert_eq!(client.submit(1, 2, "31").unwrap(), Submission::AlreadySolved);
+        assert!(matches!(client.submit(1, 2, "31"), Err(ClientError::UnexpectedResponse(_))));
+    }
+
+    #[test]
+    fn test_throttle() {
+        let (url, _requests) = mock_server(vec![(200, "1"), (200, "2"), (200, "3")]);
+        let inputs = tempfile::tempdir().unwrap();
+        let mut first = client(url.clone(), inputs.path());
+        first.config.min_interval = Duration::from_millis(200);
+
+        let start = Instant::now();
+        first.fetch_input(1).unwrap();
+        first.fetch_input(2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        // a new client, as every `aoc fetch` or `aoc submit` makes, still waits for the last one
+        let mut second = client(url, inputs.path());
+        second.config.min_interval = Duration::from_millis(200);
+        let start = Instant::now();
+        second.fetch_input(3).unwrap();
+        // the file only keeps whole milliseconds
+        assert!(start.elapsed() >= Duration::from_millis(199));
+    }
+
+    #[test]
+    fn test_parse_wait() {
+        assert_eq!(parse_wait("You have 38s left to wait."), Some(Duration::from_secs(38)));
+        assert_eq!(parse_wait("You have 4m 0s left to wait."), Some(Duration::from_secs(240)));
+        assert_eq!(parse_wait("Please wait one minute."), None);
+    }
+}
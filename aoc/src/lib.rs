@@ -0,0 +1,223 @@
+use std::any::Any;
+use std::fmt;
+use std::panic;
+
+pub mod client;
+pub mod server;
+pub mod watch;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SolveError {
+    UnknownDay(u8),
+    UnknownPart(u8),
+    InvalidInput(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::UnknownDay(day) => write!(f, "no solver for day {}", day),
+            SolveError::UnknownPart(part) => write!(f, "no part {}, expected 1 or 2", part),
+            SolveError::InvalidInput(reason) => write!(f, "invalid puzzle input: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+type Solver = fn(&str) -> Result<u64, SolveError>;
+
+// Answers leave as u64, a negative or wider one is reported rather than truncated.
+fn answer<T: Copy + fmt::Display + TryInto<u64>>(value: T) -> Result<u64, SolveError> {
+    value.try_into().map_err(|_| SolveError::InvalidInput(format!("answer {} does not fit a u64", value)))
+}
+
+fn day1_as<T: day1::Location>(input: &str, part: u8) -> Result<u64, SolveError> {
+    let parsed = day1::parse_input::<T>(input);
+    match part {
+        1 => answer(day1::part_one(&parsed)),
+        _ => answer(day1::part_two(&parsed)),
+    }
+}
+
+fn day1(input: &str, part: u8) -> Result<u64, SolveError> {
+    match day1::Width::detect(input) {
+        day1::Width::U32 => day1_as::<u32>(input, part),
+        day1::Width::U64 => day1_as::<u64>(input, part),
+        day1::Width::I64 => day1_as::<i64>(input, part),
+        day1::Width::I128 => day1_as::<i128>(input, part),
+    }
+}
+
+fn day3(input: &str, part: u8) -> Result<u64, SolveError> {
+    let rules = day3::operand::OperandRules::default();
+    let total = match part {
+        1 => day3::part_one(input, &rules),
+        _ => day3::part_two(input, &rules),
+    };
+    answer(total.map_err(|err| SolveError::InvalidInput(err.to_string()))?)
+}
+
+fn solver(day: u8, part: u8) -> Result<Solver, SolveError> {
+    if part != 1 && part != 2 {
+        return Err(SolveError::UnknownPart(part));
+    }
+
+    let solver: Solver = match (day, part) {
+        (1, 1) => |input| day1(input, 1),
+        (1, _) => |input| day1(input, 2),
+        (2, 1) => |input| Ok(day2::process(input, &day2::SafetyPolicy::default(), day2::is_safe) as u64),
+        (2, _) => |input| Ok(day2::process(input, &day2::SafetyPolicy::default(), day2::is_safe_with_removal) as u64),
+        (3, 1) => |input| day3(input, 1),
+        (3, _) => |input| day3(input, 2),
+        (4, 1) => |input| Ok(day4::part_one(&day4::parse_input(input)) as u64),
+        (4, _) => |input| Ok(day4::part_two(&day4::parse_input(input)) as u64),
+        (5, 1) => |input| {
+            let (rule_map, updates) = day5::parse_input(input);
+            Ok(day5::process(&updates, &rule_map).0 as u64)
+        },
+        (5, _) => |input| {
+            let (rule_map, updates) = day5::parse_input(input);
+            Ok(day5::process(&updates, &rule_map).1 as u64)
+        },
+        (6, 1) => |input| {
+            let (mut guard, obstacles, bounds) = day6::parse_input(input);
+            Ok(day6::process(&mut guard, &obstacles, &bounds) as u64)
+        },
+        (6, _) => |input| {
+            let (mut guard, mut obstacles, bounds) = day6::parse_input(input);
+            Ok(day6::part_two(&mut guard, &mut obstacles, &bounds) as u64)
+        },
+        (7, 1) => |input| Ok(day7::process(input, day7::part_one)),
+        (7, _) => |input| Ok(day7::process(input, day7::part_two)),
+        (8, 1) => |input| {
+            let (antennas, max_size) = day8::parse_input(input);
+            Ok(day8::part_one(&antennas, max_size) as u64)
+        },
+        (8, _) => |input| {
+            let (antennas, max_size) = day8::parse_input(input);
+            Ok(day8::part_two(&antennas, max_size) as u64)
+        },
+        _ => return Err(SolveError::UnknownDay(day)),
+    };
+    Ok(solver)
+}
+
+// The day crates assume well formed puzzle input and unwrap everything, so a panic while
+// solving is reported back as invalid input instead of taking the caller down with it.
+pub fn solve(day: u8, part: u8, input: &str) -> Result<u64, SolveError> {
+    let solver = solver(day, part)?;
+    panic::catch_unwind(|| solver(input))
+        .unwrap_or_else(|payload| Err(SolveError::InvalidInput(panic_message(payload.as_ref()))))
+}
+
+// Serializes whatever the day's parser builds. Hash maps are dumped through a BTreeMap so the
+// output is stable, and day 4's coordinate keyed grid becomes a list since JSON keys must be strings.
+#[cfg(feature = "serde")]
+pub fn dump_parsed(day: u8, input: &str) -> Result<serde_json::Value, SolveError> {
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    let dump = || match day {
+        1 => {
+            fn columns<T: day1::Location + Into<serde_json::Value>>(input: &str) -> serde_json::Value {
+                let (left, right) = day1::parse_input::<T>(input);
+                let values = |values: Vec<T>| values.into_iter().map(Into::into).collect::<Vec<serde_json::Value>>();
+                json!({ "left": values(left), "right": values(right) })
+            }
+            Ok(match day1::Width::detect(input) {
+                day1::Width::U32 => columns::<u32>(input),
+                day1::Width::U64 => columns::<u64>(input),
+                day1::Width::I64 => columns::<i64>(input),
+                // serde_json has no 128-bit numbers, these go out as strings
+                day1::Width::I128 => {
+                    let (left, right) = day1::parse_input::<i128>(input);
+                    let strings = |values: Vec<i128>| values.iter().map(i128::to_string).collect::<Vec<_>>();
+                    json!({ "left": strings(left), "right": strings(right) })
+                }
+            })
+        }
+        2 => Ok(json!({ "reports": day2::parse_input(input) })),
+        3 => Ok(json!({ "instructions": day3::scanner::scan(input, &day3::operand::OperandRules::default()) })),
+        4 => {
+            let letters: Vec<_> = day4::parse_input(input).into_iter().collect();
+            Ok(json!({ "letters": letters }))
+        }
+        5 => {
+            let (rule_map, updates) = day5::parse_input(input);
+            let rule_map: BTreeMap<_, _> = rule_map.into_iter().collect();
+            Ok(json!({ "rule_map": rule_map, "updates": updates }))
+        }
+        6 => {
+            let (guard, obstacles, bounds) = day6::parse_input(input);
+            Ok(json!({ "guard": guard, "obstacles": obstacles, "bounds": bounds }))
+        }
+        7 => Ok(json!({ "equations": day7::parse_input(input) })),
+        8 => {
+            let (antennas, max_size) = day8::parse_input(input);
+            let antennas: BTreeMap<_, _> = antennas.into_iter().collect();
+            Ok(json!({ "antennas": antennas, "max_size": max_size }))
+        }
+        _ => Err(SolveError::UnknownDay(day)),
+    };
+    panic::catch_unwind(dump).unwrap_or_else(|payload| Err(SolveError::InvalidInput(panic_message(payload.as_ref()))))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "solver panicked".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve(1, 1, "3   4\n4   3\n2   5\n1   3\n3   9\n3   3"), Ok(11));
+        assert_eq!(solve(3, 2, "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"), Ok(48));
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        assert_eq!(solve(26, 1, ""), Err(SolveError::UnknownDay(26)));
+        assert_eq!(solve(1, 3, ""), Err(SolveError::UnknownPart(3)));
+        assert!(matches!(solve(1, 1, "3 four"), Err(SolveError::InvalidInput(_))));
+
+        // negative IDs are detected and solved, a negative answer is an error rather than a panic
+        assert_eq!(solve(1, 1, "-3   4\n-5000000000   -1"), Ok(4999999999 + 7));
+        assert_eq!(solve(1, 2, "-3   -3\n1   -3"), Err(SolveError::InvalidInput("answer -6 does not fit a u64".to_string())));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_dump_parsed() {
+        let dump = dump_parsed(6, "..#\n.^.\n...").unwrap();
+        assert_eq!(dump["guard"]["location"], serde_json::json!([1, 1]));
+        assert_eq!(dump["guard"]["direction"], "Up");
+        assert_eq!(dump["obstacles"]["locations"], serde_json::json!([[0, 2]]));
+
+        let guard: day6::Guard = serde_json::from_value(dump["guard"].clone()).unwrap();
+        assert_eq!(guard.location, (1, 1));
+
+        let dump = dump_parsed(5, "47|53\n97|47\n\n97,47,53").unwrap();
+        assert_eq!(dump["rule_map"], serde_json::json!({ "47": [97], "53": [47], "97": [] }));
+
+        let dump = dump_parsed(1, "-3   4\n170141183460469231731687303715884105727   1").unwrap();
+        assert_eq!(dump["left"], serde_json::json!(["-3", "170141183460469231731687303715884105727"]));
+
+        let dump = dump_parsed(3, "xmul(2,4)do()").unwrap();
+        assert_eq!(
+            dump["instructions"],
+            serde_json::json!([
+                { "instruction": { "Mul": [2, 4] }, "span": { "start": 1, "end": 9 } },
+                { "instruction": "Do", "span": { "start": 9, "end": 13 } },
+            ])
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use aoc::client::{self, Client, ClientConfig};
+use aoc::server::{Config, Server};
+use aoc::watch::{self, Watcher};
+
+const USAGE: &str = "usage:
+    aoc run DAY [--input PATH] [--dump-parsed]
+    aoc serve [--addr ADDR] [--max-body-bytes N] [--timeout-secs N] [--max-solves N]
+    aoc fetch DAY [CLIENT OPTIONS]
+    aoc submit DAY PART [ANSWER] [CLIENT OPTIONS]
+    aoc watch DAY [--inputs-dir DIR] [--fixture PATH]... [--debounce-ms N]
+
+watch re-runs both parts whenever DAY's input or one of its fixtures changes. It only watches
+those files, not the solver sources: after editing a solver, rebuild and start it again.
+
+client options:
+    --session-file PATH    read the session token from PATH unless AOC_SESSION is set (default .aoc-session)
+    --base-url URL         (default https://adventofcode.com)
+    --inputs-dir DIR       where inputs are cached, along with the time of the last request so
+                           separate runs keep to the rate limit (default inputs)";
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn parse_flag<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> T {
+    let Some(value) = value else {
+        fail(&format!("missing value for {}", flag));
+    };
+    value.parse().unwrap_or_else(|_| fail(&format!("invalid value for {}: {}", flag, value)))
+}
+
+fn serve(args: &[String]) {
+    let mut addr = "127.0.0.1:3000".to_string();
+    let mut config = Config::default();
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--addr" => addr = parse_flag(flag, args.next()),
+            "--max-body-bytes" => config.max_body_bytes = parse_flag(flag, args.next()),
+            "--timeout-secs" => config.timeout = Duration::from_secs(parse_flag(flag, args.next())),
+            "--max-solves" => config.max_solves = parse_flag(flag, args.next()),
+            _ => fail(&format!("unknown flag: {}", flag)),
+        }
+    }
+
+    let server = Server::bind(&addr, config).unwrap_or_else(|err| {
+        eprintln!("could not bind {}: {}", addr, err);
+        process::exit(1);
+    });
+    println!("listening on http://{}", server.local_addr());
+    server.run();
+}
+
+// --dump-parsed needs the `serde` feature, e.g. `cargo run -p aoc --features serde -- run 6 --dump-parsed`
+fn run(args: &[String]) {
+    let mut input_path = None;
+    let mut dump = false;
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input_path = Some(parse_flag::<PathBuf>(arg, args.next())),
+            "--dump-parsed" => dump = true,
+            flag if flag.starts_with("--") => fail(&format!("unknown flag: {}", flag)),
+            _ => positional.push(arg),
+        }
+    }
+    let [day] = positional.as_slice() else {
+        fail("expected DAY");
+    };
+    let day: u8 = parse_flag("DAY", Some(day));
+
+    let input_path = input_path.unwrap_or_else(|| PathBuf::from(format!("inputs/day{}.txt", day)));
+    let input = exit_on_error(std::fs::read_to_string(&input_path));
+
+    if dump {
+        dump_parsed(day, &input);
+    } else {
+        let part_one = exit_on_error(aoc::solve(day, 1, &input));
+        let part_two = exit_on_error(aoc::solve(day, 2, &input));
+        println!("part 1: {} part 2: {}", part_one, part_two);
+    }
+}
+
+#[cfg(feature = "serde")]
+fn dump_parsed(day: u8, input: &str) {
+    let parsed = exit_on_error(aoc::dump_parsed(day, input));
+    println!("{}", serde_json::to_string_pretty(&parsed).unwrap());
+}
+
+#[cfg(not(feature = "serde"))]
+fn dump_parsed(_day: u8, _input: &str) {
+    eprintln!("--dump-parsed needs aoc to be built with the `serde` feature");
+    process::exit(2);
+}
+
+// splits the client options off the positional arguments and builds a client from them
+fn client(args: &[String]) -> (Client, Vec<String>) {
+    let mut session_file = PathBuf::from(".aoc-session");
+    let mut config = ClientConfig::default();
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--session-file" => session_file = parse_flag(arg, args.next()),
+            "--base-url" => config.base_url = parse_flag(arg, args.next()),
+            "--inputs-dir" => config.inputs_dir = parse_flag(arg, args.next()),
+            flag if flag.starts_with("--") => fail(&format!("unknown flag: {}", flag)),
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let session = client::load_session(&session_file).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    (Client::new(session, config), positional)
+}
+
+fn exit_on_error<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    })
+}
+
+fn fetch(args: &[String]) {
+    let (mut client, positional) = client(args);
+    let [day] = positional.as_slice() else {
+        fail("expected DAY");
+    };
+    let day = parse_flag("DAY", Some(day));
+
+    exit_on_error(client.fetch_input(day));
+    println!("{}", client.input_path(day).display());
+}
+
+// without an explicit answer the day is solved from its (fetched) input first
+fn submit(args: &[String]) {
+    let (mut client, positional) = client(args);
+    let (day, part, answer) = match positional.as_slice() {
+        [day, part] => (day, part, None),
+        [day, part, answer] => (day, part, Some(answer.clone())),
+        _ => fail("expected DAY PART [ANSWER]"),
+    };
+    let day = parse_flag("DAY", Some(day));
+    let part = parse_flag("PART", Some(part));
+
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let input = exit_on_error(client.fetch_input(day));
+            exit_on_error(aoc::solve(day, part, &input)).to_string()
+        }
+    };
+
+    let submission = exit_on_error(client.submit(day, part, &answer));
+    println!("day {} part {}: {} {}", day, part, answer, submission);
+}
+
+// re-runs both parts for the input and every fixture whenever one of them changes, the
+// solvers are the ones this binary was built with
+fn watch(args: &[String]) {
+    let mut inputs_dir = PathBuf::from("inputs");
+    let mut fixtures = Vec::new();
+    let mut debounce = Duration::from_millis(300);
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--inputs-dir" => inputs_dir = parse_flag(arg, args.next()),
+            "--fixture" => fixtures.push(parse_flag(arg, args.next())),
+            "--debounce-ms" => debounce = Duration::from_millis(parse_flag(arg, args.next())),
+            flag if flag.starts_with("--") => fail(&format!("unknown flag: {}", flag)),
+            _ => positional.push(arg),
+        }
+    }
+    let [day] = positional.as_slice() else {
+        fail("expected DAY");
+    };
+    let day = parse_flag("DAY", Some(day));
+
+    let mut watcher = Watcher::new(day, &inputs_dir, fixtures, debounce);
+    let mut previous = HashMap::new();
+    loop {
+        for path in watcher.files() {
+            let answers = match std::fs::read_to_string(&path) {
+                Ok(input) => watch::run(day, &input),
+                Err(err) => [Err(err.to_string()), Err(err.to_string())],
+            };
+            println!("{}", path.display());
+            for line in watch::diff(previous.get(&path), &answers) {
+                println!("    {}", line);
+            }
+            previous.insert(path, answers);
+        }
+        println!();
+        watcher.wait();
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("serve") => serve(&args[1..]),
+        Some("fetch") => fetch(&args[1..]),
+        Some("submit") => submit(&args[1..]),
+        Some("watch") => watch(&args[1..]),
+        Some(command) => fail(&format!("unknown command: {}", command)),
+        None => fail("missing command"),
+    }
+}
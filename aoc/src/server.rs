@@ -0,0 +1,258 @@
+use std::io::{self, Read};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::SolveError;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub max_body_bytes: usize,
+    pub timeout: Duration,
+    // solves running at once, including ones whose request already timed out
+    pub max_solves: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_body_bytes: 1024 * 1024,
+            timeout: Duration::from_secs(30),
+            max_solves: 8,
+        }
+    }
+}
+
+pub struct Server {
+    http: tiny_http::Server,
+    config: Config,
+    solves: Arc<AtomicUsize>,
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, config: Config) -> io::Result<Self> {
+        let http = tiny_http::Server::http(addr).map_err(io::Error::other)?;
+        Ok(Server { http, config, solves: Arc::new(AtomicUsize::new(0)) })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.http.server_addr().to_ip().unwrap()
+    }
+
+    // Blocks until `shutdown` is called, handling every request on its own thread.
+    pub fn run(&self) {
+        for mut request in self.http.incoming_requests() {
+            let config = self.config.clone();
+            let solves = Arc::clone(&self.solves);
+            thread::spawn(move || {
+                let (status, body) = handle(&mut request, &config, &solves);
+                let response = Response::from_string(body.to_string())
+                    .with_status_code(status)
+                    .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+                let _ = request.respond(response);
+            });
+        }
+    }
+
+    pub fn shutdown(&self) {
+        self.http.unblock();
+    }
+}
+
+// A slot among the running solves, given back when the solver thread finishes.
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    fn acquire(solves: &Arc<AtomicUsize>, max: usize) -> Option<Slot> {
+        solves.fetch_update(Ordering::AcqRel, Ordering::Acquire, |running| (running < max).then_some(running + 1)).ok()?;
+        Some(Slot(Arc::clone(solves)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn error(status: u16, message: impl ToString) -> (u16, Value) {
+    (status, json!({ "error": message.to_string() }))
+}
+
+fn handle(request: &mut Request, config: &Config, solves: &Arc<AtomicUsize>) -> (u16, Value) {
+    let Some((day, part)) = parse_path(request.url()) else {
+        return error(404, format!("no route for {}", request.url()));
+    };
+    if request.method() != &Method::Post {
+        return error(405, format!("{} not allowed, use POST", request.method()));
+    }
+    if request.body_length().is_some_and(|length| length > config.max_body_bytes) {
+        return error(413, format!("body exceeds {} bytes", config.max_body_bytes));
+    }
+
+    // the declared length is missing for chunked bodies, so cap the read as well
+    let mut body = Vec::new();
+    let limit = config.max_body_bytes as u64 + 1;
+    if let Err(err) = request.as_reader().take(limit).read_to_end(&mut body) {
+        return error(400, format!("could not read body: {}", err));
+    }
+    if body.len() > config.max_body_bytes {
+        return error(413, format!("body exceeds {} bytes", config.max_body_bytes));
+    }
+    let Ok(input) = String::from_utf8(body) else {
+        return error(400, "body is not valid UTF-8");
+    };
+
+    // a solver that runs past the timeout can't be interrupted, it is left to finish on its
+    // own thread and its answer is dropped, but it holds on to its slot until then
+    let Some(slot) = Slot::acquire(solves, config.max_solves) else {
+        return error(503, format!("{} solves are already running, try again later", config.max_solves));
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _slot = slot;
+        let _ = sender.send(crate::solve(day, part, &input));
+    });
+
+    match receiver.recv_timeout(config.timeout) {
+        Ok(Ok(answer)) => (200, json!({ "day": day, "part": part, "answer": answer })),
+        Ok(Err(err @ SolveError::InvalidInput(_))) => error(422, err),
+        Ok(Err(err)) => error(404, err),
+        Err(RecvTimeoutError::Timeout) => error(
+            504,
+            format!("solver did not finish within {:?}, it was abandoned rather than cancelled and keeps running", config.timeout),
+        ),
+        Err(RecvTimeoutError::Disconnected) => error(500, "solver exited without an answer"),
+    }
+}
+
+// matches `/day/{n}/part/{p}`, ignoring any query string
+fn parse_path(url: &str) -> Option<(u8, u8)> {
+    let path = url.split('?').next()?;
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match segments[..] {
+        ["day", day, "part", part] => Some((day.parse().ok()?, part.parse().ok()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY1_INPUT: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+    fn start(config: Config) -> (Arc<Server>, String) {
+        let server = Arc::new(Server::bind("127.0.0.1:0", config).unwrap());
+        let url = format!("http://{}", server.local_addr());
+        let running = Arc::clone(&server);
+        thread::spawn(move || running.run());
+        (server, url)
+    }
+
+    fn post(url: &str, body: &str) -> (u16, Value) {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into();
+        let mut response = agent.post(url).send(body).unwrap();
+        let status = response.status().as_u16();
+        let body = response.body_mut().read_to_string().unwrap();
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(parse_path("/day/1/part/2"), Some((1, 2)));
+        assert_eq!(parse_path("/day/8/part/1?verbose"), Some((8, 1)));
+        assert_eq!(parse_path("/day/one/part/1"), None);
+        assert_eq!(parse_path("/day/1"), None);
+    }
+
+    #[test]
+    fn test_solves() {
+        let (server, url) = start(Config::default());
+
+        let (status, body) = post(&format!("{}/day/1/part/1", url), DAY1_INPUT);
+        assert_eq!(status, 200);
+        assert_eq!(body, json!({ "day": 1, "part": 1, "answer": 11 }));
+
+        let (status, body) = post(&format!("{}/day/1/part/2", url), DAY1_INPUT);
+        assert_eq!(status, 200);
+        assert_eq!(body["answer"], 31);
+
+        server.shutdown();
+    }
+
+    #[test]
+    fn test_errors() {
+        let config = Config { max_body_bytes: 64, ..Config::default() };
+        let (server, url) = start(config);
+
+        let (status, body) = post(&format!("{}/day/1/part/1", url), "3   four");
+        assert_eq!(status, 422);
+        assert!(body["error"].as_str().unwrap().starts_with("invalid puzzle input"));
+
+        let (status, _) = post(&format!("{}/day/26/part/1", url), DAY1_INPUT);
+        assert_eq!(status, 404);
+
+        let (status, _) = post(&format!("{}/day/1/part/3", url), DAY1_INPUT);
+        assert_eq!(status, 404);
+
+        let (status, _) = post(&format!("{}/solve", url), DAY1_INPUT);
+        assert_eq!(status, 404);
+
+        let (status, _) = post(&format!("{}/day/1/part/1", url), &DAY1_INPUT.repeat(4));
+        assert_eq!(status, 413);
+
+        let response = ureq::get(&format!("{}/day/1/part/1", url)).call();
+        assert!(matches!(response, Err(ureq::Error::StatusCode(405))));
+
+        server.shutdown();
+    }
+
+    #[test]
+    fn test_timeout() {
+        let config = Config { timeout: Duration::from_millis(1), max_solves: 1, ..Config::default() };
+        let (server, url) = start(config);
+
+        // an open floor gives day 6's brute force part two far more than 1ms of work
+        let mut grid = vec![".".repeat(100); 100];
+        grid[98].replace_range(50..51, "^");
+        let (status, body) = post(&format!("{}/day/6/part/2", url), &grid.join("\n"));
+        assert_eq!(status, 504);
+        assert!(body["error"].as_str().unwrap().contains("abandoned"));
+
+        // the abandoned solve still has the only slot, retries are turned away
+        let (status, _) = post(&format!("{}/day/6/part/2", url), &grid.join("\n"));
+        assert_eq!(status, 503);
+        let (status, _) = post(&format!("{}/day/1/part/1", url), DAY1_INPUT);
+        assert_eq!(status, 503);
+
+        server.shutdown();
+    }
+
+    #[test]
+    fn test_slots() {
+        let solves = Arc::new(AtomicUsize::new(0));
+        let first = Slot::acquire(&solves, 2).unwrap();
+        let second = Slot::acquire(&solves, 2).unwrap();
+        assert!(Slot::acquire(&solves, 2).is_none());
+        drop(first);
+        assert!(Slot::acquire(&solves, 2).is_some());
+        drop(second);
+        assert_eq!(solves.load(Ordering::Acquire), 0);
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+// modification time and length, `len` catches edits that land within the mtime granularity
+type Snapshot = BTreeMap<PathBuf, Option<(SystemTime, u64)>>;
+
+pub type Answers = [Result<u64, String>; 2];
+
+// Watches a day's input plus its fixtures, which are the other `dayN.*` files next to it
+// (`day1.example.txt` for instance) and any extra paths given explicitly. Source files are
+// not watched.
+pub struct Watcher {
+    day: u8,
+    inputs_dir: PathBuf,
+    fixtures: Vec<PathBuf>,
+    debounce: Duration,
+    poll_interval: Duration,
+    snapshot: Snapshot,
+    pending: Option<Instant>,
+}
+
+impl Watcher {
+    pub fn new(day: u8, inputs_dir: &Path, fixtures: Vec<PathBuf>, debounce: Duration) -> Self {
+        let mut watcher = Watcher {
+            day,
+            inputs_dir: inputs_dir.to_path_buf(),
+            fixtures,
+            debounce,
+            poll_interval: Duration::from_millis(100).min(debounce),
+            snapshot: Snapshot::new(),
+            pending: None,
+        };
+        watcher.snapshot = watcher.snapshot();
+        watcher
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.snapshot
+            .iter()
+            .filter(|(_, modified)| modified.is_some())
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let prefix = format!("day{}.", self.day);
+        let mut paths = self.fixtures.clone();
+        paths.push(self.inputs_dir.join(format!("day{}.txt", self.day)));
+
+        if let Ok(entries) = fs::read_dir(&self.inputs_dir) {
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                    paths.push(entry.path());
+                }
+            }
+        }
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let modified = fs::metadata(&path)
+                    .ok()
+                    .filter(|metadata| metadata.is_file())
+                    .and_then(|metadata| Some((metadata.modified().ok()?, metadata.len())));
+                (path, modified)
+            })
+            .collect()
+    }
+
+    // Returns true once the files have changed and then stayed untouched for the debounce
+    // period, so a burst of saves only triggers a single run.
+    pub fn poll(&mut self) -> bool {
+        let snapshot = self.snapshot();
+        if snapshot != self.snapshot {
+            self.snapshot = snapshot;
+            self.pending = Some(Instant::now());
+            return false;
+        }
+
+        match self.pending {
+            Some(changed) if changed.elapsed() >= self.debounce => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn wait(&mut self) {
+        while !self.poll() {
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
+
+pub fn run(day: u8, input: &str) -> Answers {
+    [1, 2].map(|part| crate::solve(day, part, input).map_err(|err| err.to_string()))
+}
+
+fn describe(answer: &Result<u64, String>) -> String {
+    match answer {
+        Ok(answer) => answer.to_string(),
+        Err(err) => format!("error ({})", err),
+    }
+}
+
+pub fn diff(previous: Option<&Answers>, current: &Answers) -> Vec<String> {
+    current
+        .iter()
+        .enumerate()
+        .map(|(idx, answer)| {
+            let part = idx + 1;
+            match previous.map(|previous| &previous[idx]) {
+                None => format!("part {}: {}", part, describe(answer)),
+                Some(previous) if previous == answer => format!("part {}: {} (unchanged)", part, describe(answer)),
+                Some(previous) => format!("part {}: {} -> {}", part, describe(previous), describe(answer)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_debounces() {
+        let inputs = tempfile::tempdir().unwrap();
+        fs::write(inputs.path().join("day1.txt"), "3   4").unwrap();
+        fs::write(inputs.path().join("day10.txt"), "ignored").unwrap();
+
+        let mut watcher = Watcher::new(1, inputs.path(), Vec::new(), Duration::from_millis(50));
+        assert_eq!(watcher.files(), vec![inputs.path().join("day1.txt")]);
+        assert!(!watcher.poll());
+
+        fs::write(inputs.path().join("day1.txt"), "3   4\n4   3").unwrap();
+        fs::write(inputs.path().join("day1.example.txt"), "1   1").unwrap();
+        assert!(!watcher.poll());
+        thread::sleep(Duration::from_millis(60));
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+
+        assert_eq!(
+            watcher.files(),
+            vec![inputs.path().join("day1.example.txt"), inputs.path().join("day1.txt")]
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let first = run(1, "3   4\n4   3");
+        assert_eq!(diff(None, &first), vec!["part 1: 0", "part 2: 7"]);
+
+        let second = run(1, "3   5\n4   3");
+        assert_eq!(diff(Some(&first), &second), vec!["part 1: 0 -> 1", "part 2: 7 -> 3"]);
+        assert_eq!(diff(Some(&second), &second), vec!["part 1: 1 (unchanged)", "part 2: 3 (unchanged)"]);
+
+        let broken = run(1, "3");
+        assert!(diff(Some(&second), &broken)[0].starts_with("part 1: 1 -> error (invalid puzzle input"));
+    }
+}
@@ -0,0 +1,303 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+
+use crate::{add_distance, add_similarity, contribution, similarity_score, total_distance, Location};
+
+// Total distance and similarity score of inputs too large to sort in memory. Sorted runs
+// are spilled to disk and merged back, a bounded number at a time.
+
+// buffer size of every run reader and writer, counted against the memory budget
+const IO_BUFFER: usize = 8 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    // bytes the two lists' buffers, and later the run readers, may hold together
+    pub memory_budget: usize,
+    pub temp_dir: PathBuf,
+    // most runs merged at once per list, which also bounds the open files
+    pub max_fan_in: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { memory_budget: 64 * 1024 * 1024, temp_dir: std::env::temp_dir(), max_fan_in: 128 }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Totals {
+    pub total_distance: u128,
+    pub similarity_score: i128,
+    // sorted runs written per list, 0 when everything fit in the budget
+    pub spilled_runs: usize,
+    // passes that merged runs into longer ones before the final merge
+    pub merge_passes: usize,
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn parse<T: Location>(value: &str) -> io::Result<T> {
+    value.parse().map_err(|err| invalid_data(format!("invalid location ID {}: {}", value, err)))
+}
+
+struct Column<T> {
+    name: &'static str,
+    buffer: Vec<T>,
+    runs: Vec<PathBuf>,
+    written: usize,
+}
+
+impl<T: Location> Column<T> {
+    fn new(name: &'static str) -> Self {
+        Column { name, buffer: Vec::new(), runs: Vec::new(), written: 0 }
+    }
+
+    fn create_run(&mut self, dir: &Path) -> io::Result<(PathBuf, BufWriter<File>)> {
+        let path = dir.join(format!("{}-{}.txt", self.name, self.written));
+        self.written += 1;
+        let writer = BufWriter::with_capacity(IO_BUFFER, File::create(&path)?);
+        Ok((path, writer))
+    }
+
+    fn spill(&mut self, dir: &Path) -> io::Result<()> {
+        let (path, mut writer) = self.create_run(dir)?;
+
+        let mut buffer = mem::take(&mut self.buffer);
+        buffer.sort();
+        for value in &buffer {
+            writeln!(writer, "{}", value)?;
+        }
+        writer.flush()?;
+
+        // keep the allocation for the next run
+        buffer.clear();
+        self.buffer = buffer;
+        self.runs.push(path);
+        Ok(())
+    }
+
+    // Merges groups of runs into longer ones until at most `fan_in` are left, returning the
+    // number of passes.
+    fn reduce(&mut self, dir: &Path, fan_in: usize) -> io::Result<usize> {
+        let mut passes = 0;
+        while self.runs.len() > fan_in {
+            let runs = mem::take(&mut self.runs);
+            for group in runs.chunks(fan_in) {
+                let (path, mut writer) = self.create_run(dir)?;
+                let mut merge = Merge::<T>::open(group)?;
+                for value in merge.by_ref() {
+                    writeln!(writer, "{}", value)?;
+                }
+                merge.finish()?;
+                writer.flush()?;
+                for run in group {
+                    std::fs::remove_file(run)?;
+                }
+                self.runs.push(path);
+            }
+            passes += 1;
+        }
+        Ok(passes)
+    }
+
+    fn merge(&self) -> io::Result<Merge<T>> {
+        Merge::open(&self.runs)
+    }
+}
+
+// A k-way merge of sorted runs. Reading stops at the first bad line, `finish` reports it.
+struct Merge<T> {
+    readers: Vec<Lines<BufReader<File>>>,
+    // the smallest unread value of each run, with the run it came from
+    heap: BinaryHeap<Reverse<(T, usize)>>,
+    error: Option<io::Error>,
+}
+
+impl<T: Location> Merge<T> {
+    fn open(runs: &[PathBuf]) -> io::Result<Self> {
+        let mut merge = Merge { readers: Vec::new(), heap: BinaryHeap::new(), error: None };
+        for path in runs {
+            merge.readers.push(BufReader::with_capacity(IO_BUFFER, File::open(path)?).lines());
+            merge.refill(merge.readers.len() - 1);
+        }
+        Ok(merge)
+    }
+
+    fn refill(&mut self, run: usize) {
+        match self.readers[run].next().map(|line| line.and_then(|line| parse(&line))) {
+            Some(Ok(value)) => self.heap.push(Reverse((value, run))),
+            Some(Err(err)) => self.error = Some(err),
+            None => {}
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
+
+impl<T: Location> Iterator for Merge<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.error.is_some() {
+            return None;
+        }
+        let Reverse((value, run)) = self.heap.pop()?;
+        self.refill(run);
+        Some(value)
+    }
+}
+
+// Collapses a sorted stream into (value, count) runs of equal values.
+fn runs<T: Location>(values: impl Iterator<Item = T>) -> impl Iterator<Item = (T, usize)> {
+    let mut values = values.peekable();
+    std::iter::from_fn(move || {
+        let value = values.next()?;
+        let mut count = 1;
+        while values.next_if_eq(&value).is_some() {
+            count += 1;
+        }
+        Some((value, count))
+    })
+}
+
+// similarity_score for sorted streams rather than slices
+fn streamed_similarity<T: Location>(left: impl Iterator<Item = T>, right: impl Iterator<Item = T>) -> i128 {
+    let mut left = runs(left).peekable();
+    let mut right = runs(right).peekable();
+    let mut score = 0;
+
+    while let (Some(&(a, left_count)), Some(&(b, right_count))) = (left.peek(), right.peek()) {
+        match a.cmp(&b) {
+            Ordering::Less => {
+                left.next();
+            }
+            Ordering::Greater => {
+                right.next();
+            }
+            Ordering::Equal => {
+                score = add_similarity(score, contribution(a, (left_count * right_count) as u64));
+                left.next();
+                right.next();
+            }
+        }
+    }
+    score
+}
+
+pub fn solve<T: Location>(input: impl BufRead, config: &Config) -> io::Result<Totals> {
+    // the two run writers come out of the budget too
+    let capacity = (config.memory_budget.saturating_sub(2 * IO_BUFFER) / (2 * mem::size_of::<T>())).max(1);
+    let mut left = Column::<T>::new("left");
+    let mut right = Column::<T>::new("right");
+    let mut dir: Option<TempDir> = None;
+
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let mut values = line.split_ascii_whitespace();
+        let (Some(a), Some(b)) = (values.next(), values.next()) else {
+            return Err(invalid_data(format!("line {}: expected two location IDs", idx + 1)));
+        };
+        left.buffer.push(parse(a)?);
+        right.buffer.push(parse(b)?);
+
+        if left.buffer.len() >= capacity {
+            if dir.is_none() {
+                dir = Some(tempfile::tempdir_in(&config.temp_dir)?);
+            }
+            let path = dir.as_ref().unwrap().path();
+            left.spill(path)?;
+            right.spill(path)?;
+        }
+    }
+
+    let Some(dir) = dir else {
+        left.buffer.sort();
+        right.buffer.sort();
+        return Ok(Totals {
+            total_distance: total_distance(&left.buffer, &right.buffer),
+            similarity_score: similarity_score(&left.buffer, &right.buffer),
+            spilled_runs: 0,
+            merge_passes: 0,
+        });
+    };
+
+    if !left.buffer.is_empty() {
+        left.spill(dir.path())?;
+        right.spill(dir.path())?;
+    }
+    let spilled_runs = left.runs.len();
+    left.buffer = Vec::new();
+    right.buffer = Vec::new();
+
+    // both lists are merged side by side, each gets half the budget for its readers
+    let fan_in = (config.memory_budget / (2 * IO_BUFFER)).min(config.max_fan_in).max(2);
+    let merge_passes = left.reduce(dir.path(), fan_in)?.max(right.reduce(dir.path(), fan_in)?);
+
+    let (mut a, mut b) = (left.merge()?, right.merge()?);
+    let total_distance = a.by_ref().zip(b.by_ref()).fold(0, |acc, (a, b)| add_distance(acc, a.distance(b)));
+    a.finish()?;
+    b.finish()?;
+
+    let (mut a, mut b) = (left.merge()?, right.merge()?);
+    let similarity_score = streamed_similarity(a.by_ref(), b.by_ref());
+    a.finish()?;
+    b.finish()?;
+
+    Ok(Totals { total_distance, similarity_score, spilled_runs, merge_passes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+    use crate::{parse_input, part_one, part_two};
+
+    #[test]
+    fn test_example() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+        let totals = solve::<u32>(input.as_bytes(), &Config::default()).unwrap();
+        assert_eq!(totals, Totals { total_distance: 11, similarity_score: 31, spilled_runs: 0, merge_passes: 0 });
+
+        // two rows of u32 per run on top of the writer buffers, merged two at a time
+        let config = Config { memory_budget: 2 * IO_BUFFER + 16, ..Config::default() };
+        let totals = solve::<u32>(input.as_bytes(), &config).unwrap();
+        assert_eq!(totals, Totals { total_distance: 11, similarity_score: 31, spilled_runs: 3, merge_passes: 1 });
+
+        let err = solve::<u32>("3   4\n5\n".as_bytes(), &Config::default()).unwrap_err();
+        assert_eq!((err.kind(), err.to_string()), (io::ErrorKind::InvalidData, "line 2: expected two location IDs".to_string()));
+        let err = solve::<u32>("3   -4\n".as_bytes(), &config).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_matches_in_memory() {
+        let mut rng = Lcg::new(11);
+        let mut next = || rng.below(1999) as i64 - 999;
+        let input: String = (0..5000).map(|_| format!("{}   {}\n", next(), next())).collect();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        // 256 rows per run, the budget only has room for two readers per list
+        let config = Config { memory_budget: 2 * IO_BUFFER + 4096, temp_dir: temp_dir.path().to_path_buf(), max_fan_in: 128 };
+        let totals = solve::<i64>(input.as_bytes(), &config).unwrap();
+
+        let parsed = parse_input::<i64>(&input);
+        assert_eq!(totals.total_distance, part_one(&parsed));
+        assert_eq!(totals.similarity_score, part_two(&parsed));
+        assert_eq!(totals.spilled_runs, 20);
+        // 20 runs, then 10, 5, 3 and finally 2
+        assert_eq!(totals.merge_passes, 4);
+        // the runs are cleaned up once solve returns
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+}
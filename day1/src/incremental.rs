@@ -0,0 +1,409 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{add_similarity, contribution, Location};
+
+// Keeps the total distance and similarity score of two changing location lists up to date,
+// in O(sqrt(n)) amortized per insert or remove.
+
+const MIN_BLOCK_SIZE: usize = 32;
+
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    value: T,
+    left: u32,
+    right: u32,
+}
+
+// Distinct values in order. The distance is the integral of |D(x)|, where D(x) = L(x) - R(x)
+// and L(x), R(x) count the values <= x in each list, and each block keeps that integral over
+// the gaps after its entries. D is 0 past the largest value whenever the lists have the same
+// length, so that last gap is taken to be empty.
+#[derive(Debug)]
+struct Block<T> {
+    entries: Vec<Entry<T>>,
+    offset: i64,
+    // gap length summed by D relative to `offset`
+    histogram: HashMap<i64, u128>,
+    total: u128,
+    // gap length where D is >= 0
+    nonnegative: u128,
+    // integral of |D| over the block's gaps. This wraps while the lists differ in length, it is
+    // exact again once the true distance fits a u128.
+    cost: u128,
+}
+
+impl<T> Block<T> {
+    fn new(entries: Vec<Entry<T>>, offset: i64) -> Self {
+        Block { entries, offset, histogram: HashMap::new(), total: 0, nonnegative: 0, cost: 0 }
+    }
+}
+
+impl<T: Location> Block<T> {
+    fn first(&self) -> T {
+        self.entries[0].value
+    }
+
+    fn delta(&self) -> i64 {
+        self.entries.iter().map(|entry| entry.left as i64 - entry.right as i64).sum()
+    }
+
+    fn rebuild(&mut self, next_first: Option<T>) {
+        self.histogram.clear();
+        self.total = 0;
+        self.nonnegative = 0;
+        self.cost = 0;
+
+        let mut local = 0;
+        for (idx, entry) in self.entries.iter().enumerate() {
+            local += entry.left as i64 - entry.right as i64;
+            let gap = match self.entries.get(idx + 1) {
+                Some(next) => next.value.distance(entry.value),
+                None => next_first.map_or(0, |first| first.distance(entry.value)),
+            };
+
+            *self.histogram.entry(local).or_default() += gap;
+            self.total += gap;
+            if local + self.offset >= 0 {
+                self.nonnegative += gap;
+            }
+            self.cost = self.cost.wrapping_add(gap.wrapping_mul((local + self.offset).unsigned_abs() as u128));
+        }
+    }
+
+    fn gap_at(&self, local: i64) -> u128 {
+        self.histogram.get(&local).copied().unwrap_or(0)
+    }
+
+    // every gap where D >= 0 moves one further from zero, every other gap one closer
+    fn shift_up(&mut self) {
+        self.cost = self.cost.wrapping_add(self.nonnegative).wrapping_sub(self.total - self.nonnegative);
+        self.nonnegative += self.gap_at(-self.offset - 1);
+        self.offset += 1;
+    }
+
+    // gaps where D >= 1 move one closer to zero, every other gap one further
+    fn shift_down(&mut self) {
+        let positive = self.nonnegative - self.gap_at(-self.offset);
+        self.cost = self.cost.wrapping_add(self.total - positive).wrapping_sub(positive);
+        self.nonnegative = positive;
+        self.offset -= 1;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Debug)]
+pub struct LocationLists<T> {
+    blocks: Vec<Block<T>>,
+    distinct: usize,
+    len_left: usize,
+    len_right: usize,
+    similarity: i128,
+    // updates since the blocks were last cut to size
+    updates: usize,
+}
+
+impl<T: Location> Default for LocationLists<T> {
+    fn default() -> Self {
+        LocationLists { blocks: Vec::new(), distinct: 0, len_left: 0, len_right: 0, similarity: 0, updates: 0 }
+    }
+}
+
+impl<T: Location> LocationLists<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_lists(left: &[T], right: &[T]) -> Self {
+        let mut counts: BTreeMap<T, Entry<T>> = BTreeMap::new();
+        for &value in left {
+            counts.entry(value).or_insert(Entry { value, left: 0, right: 0 }).left += 1;
+        }
+        for &value in right {
+            counts.entry(value).or_insert(Entry { value, left: 0, right: 0 }).right += 1;
+        }
+
+        let entries: Vec<Entry<T>> = counts.into_values().collect();
+        let similarity = entries
+            .iter()
+            .fold(0, |acc, entry| add_similarity(acc, contribution(entry.value, entry.left as u64 * entry.right as u64)));
+        let mut lists = LocationLists {
+            blocks: Vec::new(),
+            distinct: entries.len(),
+            len_left: left.len(),
+            len_right: right.len(),
+            similarity,
+            updates: 0,
+        };
+        lists.cut(entries);
+        lists
+    }
+
+    pub fn len_left(&self) -> usize {
+        self.len_left
+    }
+
+    pub fn len_right(&self) -> usize {
+        self.len_right
+    }
+
+    // only defined while both lists have the same length
+    pub fn total_distance(&self) -> Option<u128> {
+        if self.len_left != self.len_right {
+            return None;
+        }
+        Some(self.blocks.iter().fold(0, |acc, block| acc.wrapping_add(block.cost)))
+    }
+
+    pub fn similarity_score(&self) -> i128 {
+        self.similarity
+    }
+
+    pub fn insert_left(&mut self, value: T) {
+        self.update(value, Side::Left, true);
+    }
+
+    pub fn insert_right(&mut self, value: T) {
+        self.update(value, Side::Right, true);
+    }
+
+    // returns false when the value is not in the list
+    pub fn remove_left(&mut self, value: T) -> bool {
+        self.update(value, Side::Left, false)
+    }
+
+    pub fn remove_right(&mut self, value: T) -> bool {
+        self.update(value, Side::Right, false)
+    }
+
+    fn block_size(&self) -> usize {
+        self.distinct.isqrt().max(MIN_BLOCK_SIZE)
+    }
+
+    fn rebuild(&mut self, idx: usize) {
+        let next_first = self.blocks.get(idx + 1).map(Block::first);
+        self.blocks[idx].rebuild(next_first);
+    }
+
+    // Splits the entries into blocks of `block_size`, which takes O(n).
+    fn cut(&mut self, entries: Vec<Entry<T>>) {
+        self.blocks.clear();
+        self.updates = 0;
+        let block_size = self.block_size();
+        let mut offset = 0;
+        for chunk in entries.chunks(block_size) {
+            let block = Block::new(chunk.to_vec(), offset);
+            offset += block.delta();
+            self.blocks.push(block);
+        }
+        for idx in 0..self.blocks.len() {
+            self.rebuild(idx);
+        }
+    }
+
+    // Blocks only grow or shrink in place, so they are cut again after sqrt(n) updates. No
+    // block grows past 2 * sqrt(n) entries in between and the O(n) cut is O(sqrt(n)) amortized.
+    fn rebalance(&mut self) {
+        self.updates += 1;
+        if self.updates >= self.block_size() {
+            let entries = self.blocks.iter_mut().flat_map(|block| std::mem::take(&mut block.entries)).collect();
+            self.cut(entries);
+        }
+    }
+
+    fn update(&mut self, value: T, side: Side, insert: bool) -> bool {
+        if self.blocks.is_empty() {
+            if !insert {
+                return false;
+            }
+            self.blocks.push(Block::new(vec![Entry { value, left: 0, right: 0 }], 0));
+            self.distinct += 1;
+        }
+
+        // the last block starting at or below the value, so a new value can only become the
+        // first entry of the first block and no earlier block's last gap changes on insert
+        let idx = self.blocks.partition_point(|block| block.first() <= value).saturating_sub(1);
+        let block = &mut self.blocks[idx];
+        let position = block.entries.binary_search_by_key(&value, |entry| entry.value);
+
+        let entry = match (position, insert) {
+            (Ok(position), _) => &mut block.entries[position],
+            (Err(position), true) => {
+                block.entries.insert(position, Entry { value, left: 0, right: 0 });
+                self.distinct += 1;
+                &mut block.entries[position]
+            }
+            (Err(_), false) => return false,
+        };
+        let (count, other) = match side {
+            Side::Left => (&mut entry.left, entry.right),
+            Side::Right => (&mut entry.right, entry.left),
+        };
+        if insert {
+            *count += 1;
+            self.similarity = add_similarity(self.similarity, contribution(value, other as u64));
+        } else if *count == 0 {
+            return false;
+        } else {
+            *count -= 1;
+            self.similarity -= contribution(value, other as u64);
+        }
+        match side {
+            Side::Left => self.len_left = if insert { self.len_left + 1 } else { self.len_left - 1 },
+            Side::Right => self.len_right = if insert { self.len_right + 1 } else { self.len_right - 1 },
+        }
+
+        // D(x) for x >= value goes up for a new left value or a removed right value
+        let up = insert == (side == Side::Left);
+        for block in &mut self.blocks[idx + 1..] {
+            if up {
+                block.shift_up();
+            } else {
+                block.shift_down();
+            }
+        }
+
+        let position = position.unwrap_or_else(|position| position);
+        let emptied = {
+            let entries = &mut self.blocks[idx].entries;
+            if entries[position].left == 0 && entries[position].right == 0 {
+                entries.remove(position);
+                self.distinct -= 1;
+            }
+            entries.is_empty()
+        };
+
+        if emptied {
+            self.blocks.remove(idx);
+            if idx > 0 {
+                self.rebuild(idx - 1);
+            }
+        } else {
+            self.rebuild(idx);
+            // removing a block's first value lengthens the previous block's last gap
+            if position == 0 && idx > 0 {
+                self.rebuild(idx - 1);
+            }
+        }
+        self.rebalance();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+    use crate::{parse_input, similarity_score, total_distance};
+
+    #[test]
+    fn test_example() {
+        let (left, right) = parse_input::<u32>("3   4
+4   3
+2   5
+1   3
+3   9
+3   3");
+        let mut lists = LocationLists::from_lists(&left, &right);
+        assert_eq!(lists.total_distance(), Some(11));
+        assert_eq!(lists.similarity_score(), 31);
+
+        lists.insert_left(9);
+        assert_eq!(lists.total_distance(), None);
+        assert_eq!(lists.similarity_score(), 40);
+
+        lists.insert_right(1);
+        assert_eq!(lists.total_distance(), Some(3));
+
+        assert!(lists.remove_left(9));
+        assert!(lists.remove_right(1));
+        assert!(!lists.remove_right(1));
+        assert_eq!(lists.total_distance(), Some(11));
+        assert_eq!(lists.similarity_score(), 31);
+
+        let mut lists = LocationLists::<i64>::from_lists(&[-5000000000, 3], &[-3, 5000000000]);
+        assert_eq!(lists.total_distance(), Some(2 * 4999999997));
+        lists.insert_left(-3);
+        assert_eq!(lists.similarity_score(), -3);
+        lists.insert_right(i64::MIN);
+        assert_eq!(lists.total_distance(), Some(total_distance(&[-5000000000, -3, 3], &[i64::MIN, -3, 5000000000])));
+    }
+
+    #[test]
+    fn test_rebalance() {
+        let values: Vec<u32> = (0..5000).collect();
+        let mut lists = LocationLists::from_lists(&values, &values);
+        assert_eq!(lists.blocks.len(), 5000usize.div_ceil(70));
+
+        // thinning out every block evenly leaves none of them empty, only the cuts shrink
+        // their number
+        for value in values.iter().filter(|&&value| value % 50 != 0) {
+            assert!(lists.remove_left(*value));
+            assert!(lists.remove_right(*value));
+        }
+        assert!(lists.blocks.len() <= 2 * lists.block_size(), "{} blocks", lists.blocks.len());
+        assert_eq!(lists.total_distance(), Some(0));
+
+        lists.insert_left(1);
+        lists.insert_right(5001);
+        assert_eq!(lists.total_distance(), Some(5000));
+    }
+
+    #[test]
+    fn test_matches_recompute() {
+        let mut rng = Lcg::new(42);
+
+        let mut lists = LocationLists::new();
+        let mut left: Vec<u32> = Vec::new();
+        let mut right: Vec<u32> = Vec::new();
+
+        // enough operations to empty blocks and cut them again several times
+        for _ in 0..4000 {
+            let value = rng.below(300) as u32;
+            let on_left = rng.below(2) == 0;
+            let list = if on_left { &mut left } else { &mut right };
+
+            if rng.below(3) == 0 {
+                let position = list.iter().position(|&x| x == value);
+                let removed = if on_left { lists.remove_left(value) } else { lists.remove_right(value) };
+                assert_eq!(removed, position.is_some());
+                if let Some(position) = position {
+                    list.remove(position);
+                }
+            } else {
+                list.push(value);
+                if on_left { lists.insert_left(value) } else { lists.insert_right(value) }
+            }
+
+            let mut sorted_left = left.clone();
+            let mut sorted_right = right.clone();
+            sorted_left.sort();
+            sorted_right.sort();
+
+            assert_eq!(lists.similarity_score(), similarity_score(&sorted_left, &sorted_right));
+            if left.len() == right.len() {
+                assert_eq!(lists.total_distance(), Some(total_distance(&sorted_left, &sorted_right)));
+            } else {
+                assert_eq!(lists.total_distance(), None);
+            }
+        }
+
+        // pad the shorter list so the final state is compared as well
+        while left.len() < right.len() {
+            left.push(7);
+            lists.insert_left(7);
+        }
+        while right.len() < left.len() {
+            right.push(7);
+            lists.insert_right(7);
+        }
+        left.sort();
+        right.sort();
+        assert_eq!(lists.total_distance(), Some(total_distance(&left, &right)));
+        assert_eq!(LocationLists::from_lists(&left, &right).total_distance(), lists.total_distance());
+    }
+}
@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub mod external;
+pub mod incremental;
+pub mod pairing;
+pub mod report;
+
+#[cfg(test)]
+mod rng;
+
+// The integer types location IDs can be parsed as. Distances are summed as u128 and the
+// similarity score as i128, which is plenty up to i64 IDs. With i128 IDs either total can
+// overflow, that panics instead of wrapping.
+pub trait Location: Copy + Ord + Debug + Display + FromStr<Err = ParseIntError> {
+    fn distance(self, other: Self) -> u128;
+    fn weight(self) -> i128;
+}
+
+macro_rules! impl_location {
+    ($($t:ty),*) => {
+        $(
+            impl Location for $t {
+                fn distance(self, other: Self) -> u128 {
+                    self.abs_diff(other) as u128
+                }
+
+                fn weight(self) -> i128 {
+                    self as i128
+                }
+            }
+        )*
+    };
+}
+
+impl_location!(u32, u64, i64, i128);
+
+pub(crate) fn add_distance(total: u128, distance: u128) -> u128 {
+    total.checked_add(distance).expect("total distance overflows a u128")
+}
+
+// what `count` matches of `value` add to the similarity score
+pub(crate) fn contribution<T: Location>(value: T, count: u64) -> i128 {
+    value.weight().checked_mul(count as i128).expect("similarity score overflows an i128")
+}
+
+pub(crate) fn add_similarity(score: i128, contribution: i128) -> i128 {
+    score.checked_add(contribution).expect("similarity score overflows an i128")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    U32,
+    U64,
+    I64,
+    I128,
+}
+
+impl Width {
+    const NARROWEST_FIRST: [Width; 3] = [Width::U32, Width::U64, Width::I64];
+
+    fn fits(self, value: &str) -> bool {
+        match self {
+            Width::U32 => value.parse::<u32>().is_ok(),
+            Width::U64 => value.parse::<u64>().is_ok(),
+            Width::I64 => value.parse::<i64>().is_ok(),
+            Width::I128 => true,
+        }
+    }
+
+    // The narrowest width every value in the input fits in. Anything that does not even parse
+    // as an i128 is left for the parser to reject.
+    pub fn detect(input: &str) -> Width {
+        Width::detect_values(input.split_ascii_whitespace())
+    }
+
+    // Same as detect, for values read one at a time from an input too large to hold.
+    pub fn detect_values<S: AsRef<str>>(values: impl IntoIterator<Item = S>) -> Width {
+        let mut fits = [true; 3];
+        for value in values {
+            for (fit, width) in fits.iter_mut().zip(Width::NARROWEST_FIRST) {
+                *fit = *fit && width.fits(value.as_ref());
+            }
+        }
+
+        Width::NARROWEST_FIRST
+            .into_iter()
+            .zip(fits)
+            .find_map(|(width, fit)| fit.then_some(width))
+            .unwrap_or(Width::I128)
+    }
+}
+
+impl FromStr for Width {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "u32" => Ok(Width::U32),
+            "u64" => Ok(Width::U64),
+            "i64" => Ok(Width::I64),
+            "i128" => Ok(Width::I128),
+            _ => Err(format!("unknown width: {}, expected u32, u64, i64 or i128", s)),
+        }
+    }
+}
+
+pub fn parse_input<T: Location>(input: &str) -> (Vec<T>, Vec<T>) {
+    let (mut left, mut right) = input
+        .lines()
+        .map(|x|x.split_ascii_whitespace().collect::<Vec<&str>>())
+        .fold((Vec::new(), Vec::new()), |(mut left, mut right), vec| {
+            left.push(vec[0].parse().unwrap());
+            right.push(vec[1].parse().unwrap());
+            (left, right)
+        });
+    left.sort();
+    right.sort();
+
+    (left, right)
+}
+
+// Like parse_input but for any number of columns, every row must have the same number of
+// columns. Each column is returned sorted.
+pub fn parse_columns<T: Location>(input: &str) -> Vec<Vec<T>> {
+    let mut columns: Vec<Vec<T>> = Vec::new();
+
+    for (row, line) in input.lines().enumerate() {
+        let values: Vec<T> = line.split_ascii_whitespace().map(|x| x.parse().unwrap()).collect();
+        if row == 0 {
+            columns = vec![Vec::new(); values.len()];
+        }
+        assert_eq!(values.len(), columns.len(), "row {} has {} columns, expected {}", row + 1, values.len(), columns.len());
+
+        for (column, value) in columns.iter_mut().zip(values) {
+            column.push(value);
+        }
+    }
+    columns.iter_mut().for_each(|column| column.sort());
+
+    columns
+}
+
+pub fn part_one<T: Location>(parsed: &(Vec<T>, Vec<T>)) -> u128 {
+    total_distance(&parsed.0, &parsed.1)
+}
+
+pub fn part_two<T: Location>(parsed: &(Vec<T>, Vec<T>)) -> i128 {
+    similarity_score(&parsed.0, &parsed.1)
+}
+
+// Both lists must be sorted and of equal length, the i-th smallest values are paired up.
+pub fn total_distance<T: Location>(left: &[T], right: &[T]) -> u128 {
+    left.iter().zip(right).fold(0, |acc, (a, b)| add_distance(acc, a.distance(*b)))
+}
+
+// Both lists must be sorted (parse_input sorts them). Equal values then sit in runs, so one
+// merge walk over the two lists finds how often each left value appears on the right, and a
+// run of `a` equal values on the left matching `b` on the right contributes `value * a * b`.
+pub fn similarity_score<T: Location>(left: &[T], right: &[T]) -> i128 {
+    debug_assert!(left.is_sorted() && right.is_sorted());
+
+    let mut score = 0;
+    let (mut i, mut j) = (0, 0);
+
+    while i < left.len() && j < right.len() {
+        match left[i].cmp(&right[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                let value = left[i];
+                let left_run = left[i..].iter().take_while(|&&x| x == value).count();
+                let right_run = right[j..].iter().take_while(|&&x| x == value).count();
+                score = add_similarity(score, contribution(value, (left_run * right_run) as u64));
+                i += left_run;
+                j += right_run;
+            }
+        }
+    }
+    score
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub left: usize,
+    pub right: usize,
+    pub total_distance: u128,
+    pub similarity_score: i128,
+}
+
+// Every unordered pair of column indices, the lower index on the left. The similarity score
+// is not symmetric, pass `(right, left)` to compare_columns for the other direction.
+pub fn all_pairs(columns: usize) -> Vec<(usize, usize)> {
+    (0..columns)
+        .flat_map(|left| (left + 1..columns).map(move |right| (left, right)))
+        .collect()
+}
+
+pub fn compare_columns<T: Location>(columns: &[Vec<T>], pairs: &[(usize, usize)]) -> Vec<Comparison> {
+    pairs
+        .iter()
+        .map(|&(left, right)| Comparison {
+            left,
+            right,
+            total_distance: total_distance(&columns[left], &columns[right]),
+            similarity_score: similarity_score(&columns[left], &columns[right]),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+
+    const INPUT: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+    #[test]
+    fn test_part_one() {
+        let parsed = parse_input::<u32>(INPUT);
+        let part_one = part_one(&parsed);
+        assert_eq!(part_one, 11);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let parsed = parse_input::<u32>(INPUT);
+        let part_two = part_two(&parsed);
+        assert_eq!(part_two, 31);
+    }
+
+    #[test]
+    fn test_wide_and_signed() {
+        assert_eq!(Width::detect(INPUT), Width::U32);
+        assert_eq!(Width::detect("5000000000   1"), Width::U64);
+        assert_eq!(Width::detect("-3   4"), Width::I64);
+        assert_eq!(Width::detect("-3   18446744073709551615"), Width::I128);
+        assert_eq!("i128".parse(), Ok(Width::I128));
+
+        let parsed = parse_input::<i64>("-3   4
+5000000000   -1
+-3   -3");
+        assert_eq!(parsed.0, vec![-3, -3, 5000000000]);
+        assert_eq!(part_one(&parsed), 2 + 4999999996);
+        assert_eq!(part_two(&parsed), -6);
+
+        // the similarity product no longer overflows the ID type
+        let parsed = parse_input::<u32>("4000000000   4000000000
+1   4000000000");
+        assert_eq!(part_two(&parsed), 8000000000);
+    }
+
+    #[test]
+    #[should_panic(expected = "similarity score overflows an i128")]
+    fn test_i128_overflow() {
+        let max = i128::MAX.to_string();
+        part_two(&parse_input::<i128>(&format!("{max}   {max}\n{max}   {max}")));
+    }
+
+    #[test]
+    fn test_compare_columns() {
+        let columns = parse_columns::<u32>("3   4   3
+4   3   1
+2   5   3
+1   3   2
+3   9   3
+3   3   3");
+        assert_eq!(columns[2], vec![1, 2, 3, 3, 3, 3]);
+        assert_eq!(all_pairs(3), vec![(0, 1), (0, 2), (1, 2)]);
+
+        let comparisons = compare_columns(&columns, &all_pairs(3));
+        assert_eq!(comparisons[0], Comparison { left: 0, right: 1, total_distance: 11, similarity_score: 31 });
+        assert_eq!(comparisons[1], Comparison { left: 0, right: 2, total_distance: 1, similarity_score: 39 });
+        assert_eq!(comparisons[2], Comparison { left: 1, right: 2, total_distance: 12, similarity_score: 36 });
+    }
+
+    #[test]
+    fn test_similarity_score_matches_scan() {
+        // small values so there are plenty of repeats on both sides
+        let mut rng = Lcg::new(7);
+        let mut left: Vec<u32> = (0..500).map(|_| rng.below(50) as u32).collect();
+        let mut right: Vec<u32> = (0..500).map(|_| rng.below(50) as u32).collect();
+        left.sort();
+        right.sort();
+
+        let scan = left.iter().fold(0, |acc, to_find| {
+            acc + (to_find * right.iter().filter(|&x| x == to_find).count() as u32) as i128
+        });
+        assert_eq!(similarity_score(&left, &right), scan);
+        assert_eq!(similarity_score::<u32>(&[], &right), 0);
+    }
+}
\ No newline at end of file
@@ -1,65 +1,153 @@
-fn parse_input(input: &str) -> (Vec<u32>, Vec<u32>){
-    let (mut left, mut right) = input
-        .lines()
-        .map(|x|x.split_ascii_whitespace().collect::<Vec<&str>>())
-        .fold((Vec::new(), Vec::new()), |(mut left, mut right), vec| {
-            left.push(vec[0].parse().unwrap());
-            right.push(vec[1].parse().unwrap());
-            (left, right)
-        });
-    left.sort();
-    right.sort();
-
-    (left, right)
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process;
+
+use day1::external::{self, Config};
+use day1::report::{report, Report};
+use day1::{parse_input, part_one, part_two, Location, Width};
+
+const USAGE: &str = "usage:
+    day1 [--width auto|u32|u64|i64|i128] [--memory-budget BYTES] [--temp-dir PATH] [--max-fan-in N]
+    day1 report [--format json|csv] [--table pairs|similarity|summary|histogram] [--input PATH]
+                [--width auto|u32|u64|i64|i128]
+
+the width defaults to auto, the narrowest type every ID fits in
+a memory budget streams the input and sorts it on disk once the lists outgrow the budget,
+merging at most --max-fan-in runs (128 by default) at once
+csv prints a single table (pairs by default), json prints all of them";
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
 }
 
-fn main() {
-    let input = std::fs::read_to_string("./inputs/day1.txt").unwrap();
-    let parsed = parse_input(&input);
-    
-    let part_one = (0..parsed.0.len()).fold(0, |acc, idx| {
-        acc + parsed.0[idx].abs_diff(parsed.1[idx])
-    });
+#[cfg(feature = "serde")]
+fn render_json<T: Location + serde::Serialize>(report: &Report<T>) -> String {
+    report.to_json() + "\n"
+}
+
+#[cfg(not(feature = "serde"))]
+fn render_json<T: Location>(_report: &Report<T>) -> String {
+    fail("json needs day1 to be built with the `serde` feature, use --format csv")
+}
+
+// `json` is render_json, taken as a parameter so its Serialize bound is checked per width
+fn render_report<T: Location>(input: &str, format: &str, table: &str, json: fn(&Report<T>) -> String) -> String {
+    let (left, right) = parse_input::<T>(input);
+    let report = report(&left, &right);
+
+    match (format, table) {
+        ("json", _) => json(&report),
+        ("csv", "pairs") => report.pairs_csv(),
+        ("csv", "similarity") => report.similarity_csv(),
+        ("csv", "summary") => report.summary_csv(),
+        ("csv", "histogram") => report.histogram_csv(),
+        ("csv", table) => fail(&format!("unknown table: {}", table)),
+        (format, _) => fail(&format!("unknown format: {}", format)),
+    }
+}
+
+fn print_report(args: &[String]) {
+    let mut format = "json";
+    let mut table = "pairs";
+    let mut input_path = "./inputs/day1.txt";
+    let mut width = None;
 
-    let part_two = (0..parsed.0.len()).fold(0, |acc, idx| {
-        let to_find = &parsed.0[idx];
-        let num_found = parsed.1.iter().filter(|&x| x == to_find).count();
-        acc + (to_find * num_found as u32)
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let Some(value) = args.next() else {
+            fail(&format!("missing value for {}", flag));
+        };
+        match flag.as_str() {
+            "--format" => format = value,
+            "--table" => table = value,
+            "--input" => input_path = value,
+            "--width" if value == "auto" => width = None,
+            "--width" => width = Some(value.parse().unwrap_or_else(|message: String| fail(&message))),
+            _ => fail(&format!("unknown flag: {}", flag)),
+        }
+    }
+
+    let input = std::fs::read_to_string(input_path).unwrap();
+    let output = match width.unwrap_or_else(|| Width::detect(&input)) {
+        Width::U32 => render_report::<u32>(&input, format, table, render_json),
+        Width::U64 => render_report::<u64>(&input, format, table, render_json),
+        Width::I64 => render_report::<i64>(&input, format, table, render_json),
+        Width::I128 => render_report::<i128>(&input, format, table, render_json),
+    };
+    print!("{}", output);
+}
+
+fn solve<T: Location>(input: &str) -> (u128, i128) {
+    let parsed = parse_input::<T>(input);
+    (part_one(&parsed), part_two(&parsed))
+}
+
+fn solve_external<T: Location>(path: &str, config: &Config) -> (u128, i128) {
+    let totals = external::solve::<T>(BufReader::new(File::open(path).unwrap()), config).unwrap_or_else(|err| {
+        eprintln!("{}: {}", path, err);
+        process::exit(1);
     });
-    
-    println!("part 1: {} part 2: {}", part_one, part_two);
+    (totals.total_distance, totals.similarity_score)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "3   4
-4   3
-2   5
-1   3
-3   9
-3   3";
-
-    #[test]
-    fn test_part_one() {
-        let parsed = parse_input(INPUT);
-
-        let part_one = (0..parsed.0.len()).fold(0, |acc, idx| {
-            acc + parsed.0[idx].abs_diff(parsed.1[idx])
-        });
-        assert_eq!(part_one, 11);
+// auto detection needs its own pass over a file that is never held in memory
+fn detect_streamed(path: &str) -> Width {
+    let lines = BufReader::new(File::open(path).unwrap()).lines().map(Result::unwrap);
+    Width::detect_values(lines.flat_map(|line| line.split_ascii_whitespace().map(String::from).collect::<Vec<_>>()))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|command| command == "report") {
+        print_report(&args[1..]);
+        return;
     }
 
-    #[test]
-    fn test_part_two() {
-        let parsed = parse_input(INPUT);
+    let input_path = "./inputs/day1.txt";
+    let mut width = None;
+    let mut config: Option<Config> = None;
 
-        let part_two = (0..parsed.0.len()).fold(0, |acc, idx| {
-            let to_find = &parsed.0[idx];
-            let num_found = parsed.1.iter().filter(|&x| x == to_find).count();
-            acc + (to_find * num_found as u32)
-        });
-        assert_eq!(part_two, 31);
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let Some(value) = args.next() else {
+            fail(&format!("missing value for {}", flag));
+        };
+        match flag.as_str() {
+            "--width" if value == "auto" => width = None,
+            "--width" => width = Some(value.parse().unwrap_or_else(|message: String| fail(&message))),
+            "--memory-budget" => {
+                let memory_budget = value.parse().unwrap_or_else(|_| fail(&format!("invalid memory budget: {}", value)));
+                config.get_or_insert_with(Config::default).memory_budget = memory_budget;
+            }
+            "--temp-dir" => config.get_or_insert_with(Config::default).temp_dir = PathBuf::from(value),
+            "--max-fan-in" => {
+                let max_fan_in = value.parse().ok().filter(|&n| n >= 2);
+                let max_fan_in = max_fan_in.unwrap_or_else(|| fail(&format!("invalid fan-in: {}, expected at least 2", value)));
+                config.get_or_insert_with(Config::default).max_fan_in = max_fan_in;
+            }
+            _ => fail(&format!("unknown flag: {}", flag)),
+        }
     }
-}
\ No newline at end of file
+
+    let (part_one, part_two) = match config {
+        Some(config) => match width.unwrap_or_else(|| detect_streamed(input_path)) {
+            Width::U32 => solve_external::<u32>(input_path, &config),
+            Width::U64 => solve_external::<u64>(input_path, &config),
+            Width::I64 => solve_external::<i64>(input_path, &config),
+            Width::I128 => solve_external::<i128>(input_path, &config),
+        },
+        None => {
+            let input = std::fs::read_to_string(input_path).unwrap();
+            match width.unwrap_or_else(|| Width::detect(&input)) {
+                Width::U32 => solve::<u32>(&input),
+                Width::U64 => solve::<u64>(&input),
+                Width::I64 => solve::<i64>(&input),
+                Width::I128 => solve::<i128>(&input),
+            }
+        }
+    };
+
+    println!("part 1: {} part 2: {}", part_one, part_two);
+}
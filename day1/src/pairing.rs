@@ -0,0 +1,149 @@
+use crate::Location;
+
+// Pairs up two location lists of different lengths, leaving values unmatched at a fixed
+// penalty. Optimal pairings never cross, so a DP over the sorted lists finds one in O(n * m).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    // the day1 total distance
+    SumOfGaps,
+    // the largest gap in the pairing, an unmatched value counts as a gap of `penalty`
+    MaxGap,
+    SumOfSquaredGaps,
+}
+
+impl Objective {
+    // costs saturate, a squared gap of two far apart 128-bit IDs does not fit anything
+    fn gap_cost<T: Location>(&self, a: T, b: T) -> u128 {
+        let gap = a.distance(b);
+        match self {
+            Objective::SumOfGaps | Objective::MaxGap => gap,
+            Objective::SumOfSquaredGaps => gap.saturating_mul(gap),
+        }
+    }
+
+    fn combine(&self, acc: u128, cost: u128) -> u128 {
+        match self {
+            Objective::SumOfGaps | Objective::SumOfSquaredGaps => acc.saturating_add(cost),
+            Objective::MaxGap => acc.max(cost),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Pairing<T> {
+    pub pairs: Vec<(T, T)>,
+    pub unmatched_left: Vec<T>,
+    pub unmatched_right: Vec<T>,
+    pub cost: u128,
+}
+
+pub fn pair<T: Location>(left: &[T], right: &[T], objective: Objective, penalty: u128) -> Pairing<T> {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+    left.sort();
+    right.sort();
+
+    let width = right.len() + 1;
+    let index = |i: usize, j: usize| i * width + j;
+    let mut cost = vec![0; (left.len() + 1) * width];
+
+    for i in 0..=left.len() {
+        for j in 0..=right.len() {
+            if i == 0 && j == 0 {
+                continue;
+            }
+            let mut best = u128::MAX;
+            if i > 0 {
+                best = best.min(objective.combine(cost[index(i - 1, j)], penalty));
+            }
+            if j > 0 {
+                best = best.min(objective.combine(cost[index(i, j - 1)], penalty));
+            }
+            if i > 0 && j > 0 {
+                let gap_cost = objective.gap_cost(left[i - 1], right[j - 1]);
+                best = best.min(objective.combine(cost[index(i - 1, j - 1)], gap_cost));
+            }
+            cost[index(i, j)] = best;
+        }
+    }
+
+    // walk back from the full lists, preferring to pair values when that is as good
+    let mut pairing = Pairing {
+        pairs: Vec::new(),
+        unmatched_left: Vec::new(),
+        unmatched_right: Vec::new(),
+        cost: cost[index(left.len(), right.len())],
+    };
+    let (mut i, mut j) = (left.len(), right.len());
+
+    while i > 0 || j > 0 {
+        let current = cost[index(i, j)];
+        if i > 0 && j > 0
+            && objective.combine(cost[index(i - 1, j - 1)], objective.gap_cost(left[i - 1], right[j - 1])) == current
+        {
+            pairing.pairs.push((left[i - 1], right[j - 1]));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && objective.combine(cost[index(i - 1, j)], penalty) == current {
+            pairing.unmatched_left.push(left[i - 1]);
+            i -= 1;
+        } else {
+            pairing.unmatched_right.push(right[j - 1]);
+            j -= 1;
+        }
+    }
+    pairing.pairs.reverse();
+    pairing.unmatched_left.reverse();
+    pairing.unmatched_right.reverse();
+
+    pairing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_total_distance() {
+        let left: [u32; 6] = [3, 4, 2, 1, 3, 3];
+        let right = [4, 3, 5, 3, 9, 3];
+
+        let pairing = pair(&left, &right, Objective::SumOfGaps, u128::MAX);
+        assert_eq!(pairing.cost, 11);
+        assert_eq!(pairing.pairs, vec![(1, 3), (2, 3), (3, 3), (3, 4), (3, 5), (4, 9)]);
+        assert!(pairing.unmatched_left.is_empty() && pairing.unmatched_right.is_empty());
+
+        // dropping 1 and 9 shifts everything else into closer pairs
+        let pairing = pair(&left, &right, Objective::SumOfGaps, 2);
+        assert_eq!(pairing.cost, 7);
+        assert_eq!(pairing.unmatched_left, vec![1]);
+        assert_eq!(pairing.unmatched_right, vec![9]);
+    }
+
+    #[test]
+    fn test_unequal_lengths() {
+        let left: [u32; 3] = [10, 1, 5];
+        let right = [9, 2];
+
+        let pairing = pair(&left, &right, Objective::SumOfGaps, 3);
+        assert_eq!(pairing.pairs, vec![(1, 2), (10, 9)]);
+        assert_eq!(pairing.unmatched_left, vec![5]);
+        assert_eq!(pairing.cost, 5);
+
+        let pairing = pair(&left, &right, Objective::SumOfSquaredGaps, 100);
+        assert_eq!(pairing.pairs, vec![(1, 2), (10, 9)]);
+        assert_eq!(pairing.cost, 102);
+
+        // leaving 5 unmatched at 2 beats pairing it with either side at a gap of 3 or more
+        let pairing = pair(&left, &right, Objective::MaxGap, 2);
+        assert_eq!(pairing.pairs, vec![(1, 2), (10, 9)]);
+        assert_eq!(pairing.cost, 2);
+        let pairing = pair(&left, &right, Objective::MaxGap, 50);
+        assert_eq!(pairing.cost, 50);
+
+        let pairing = pair::<i64>(&[-6000000000, 7], &[6000000000], Objective::SumOfSquaredGaps, u128::MAX / 2);
+        assert_eq!(pairing.pairs, vec![(7, 6000000000)]);
+        assert_eq!(pairing.cost, u128::MAX / 2 + 5999999993 * 5999999993);
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt::Write;
+
+use crate::{add_distance, add_similarity, contribution, Location};
+
+// A breakdown of where the total distance and similarity score come from. Both lists must be
+// sorted and of equal length, as parse_input returns them.
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PairRow<T> {
+    pub left: T,
+    pub right: T,
+    pub difference: u128,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SimilarityRow<T> {
+    pub value: T,
+    pub occurrences: u64,
+    pub contribution: i128,
+}
+
+// differences are bucketed by powers of two: 0, 1, 2-3, 4-7, ...
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Bucket {
+    pub low: u128,
+    pub high: u128,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Summary {
+    pub total_distance: u128,
+    pub similarity_score: i128,
+    pub median_gap: f64,
+    pub max_gap: u128,
+    pub histogram: Vec<Bucket>,
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Report<T> {
+    pub pairs: Vec<PairRow<T>>,
+    pub similarity: Vec<SimilarityRow<T>>,
+    pub summary: Summary,
+}
+
+fn bucket_bounds(difference: u128) -> (u128, u128) {
+    match difference {
+        0 => (0, 0),
+        _ => {
+            let low = 1 << difference.ilog2();
+            (low, low + (low - 1))
+        }
+    }
+}
+
+pub fn report<T: Location>(left: &[T], right: &[T]) -> Report<T> {
+    let pairs: Vec<PairRow<T>> = left
+        .iter()
+        .zip(right)
+        .map(|(&left, &right)| PairRow { left, right, difference: left.distance(right) })
+        .collect();
+
+    let similarity: Vec<SimilarityRow<T>> = left
+        .iter()
+        .map(|&value| {
+            let occurrences = (right.partition_point(|&x| x <= value) - right.partition_point(|&x| x < value)) as u64;
+            SimilarityRow { value, occurrences, contribution: contribution(value, occurrences) }
+        })
+        .collect();
+
+    let mut gaps: Vec<u128> = pairs.iter().map(|pair| pair.difference).collect();
+    gaps.sort();
+    let median_gap = match gaps.len() {
+        0 => 0.0,
+        len if len % 2 == 1 => gaps[len / 2] as f64,
+        len => (gaps[len / 2 - 1] as f64 + gaps[len / 2] as f64) / 2.0,
+    };
+
+    let mut histogram: Vec<Bucket> = Vec::new();
+    for &gap in &gaps {
+        let (low, high) = bucket_bounds(gap);
+        match histogram.last_mut() {
+            Some(bucket) if bucket.low == low => bucket.count += 1,
+            _ => histogram.push(Bucket { low, high, count: 1 }),
+        }
+    }
+
+    let summary = Summary {
+        total_distance: gaps.iter().fold(0, |acc, &gap| add_distance(acc, gap)),
+        similarity_score: similarity.iter().fold(0, |acc, row| add_similarity(acc, row.contribution)),
+        median_gap,
+        max_gap: gaps.last().copied().unwrap_or(0),
+        histogram,
+    };
+
+    Report { pairs, similarity, summary }
+}
+
+impl<T: Location> Report<T> {
+    pub fn pairs_csv(&self) -> String {
+        let mut csv = String::from("left,right,difference\n");
+        for row in &self.pairs {
+            writeln!(csv, "{},{},{}", row.left, row.right, row.difference).unwrap();
+        }
+        csv
+    }
+
+    pub fn similarity_csv(&self) -> String {
+        let mut csv = String::from("value,occurrences,contribution\n");
+        for row in &self.similarity {
+            writeln!(csv, "{},{},{}", row.value, row.occurrences, row.contribution).unwrap();
+        }
+        csv
+    }
+
+    pub fn summary_csv(&self) -> String {
+        let summary = &self.summary;
+        format!(
+            "statistic,value\ntotal_distance,{}\nsimilarity_score,{}\nmedian_gap,{}\nmax_gap,{}\n",
+            summary.total_distance, summary.similarity_score, summary.median_gap, summary.max_gap
+        )
+    }
+
+    pub fn histogram_csv(&self) -> String {
+        let mut csv = String::from("low,high,count\n");
+        for bucket in &self.summary.histogram {
+            writeln!(csv, "{},{},{}", bucket.low, bucket.high, bucket.count).unwrap();
+        }
+        csv
+    }
+
+    // serde_json writes the 128-bit totals as plain numbers
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String
+    where
+        T: serde::Serialize,
+    {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    const INPUT: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+    #[test]
+    fn test_report() {
+        let (left, right) = parse_input::<u32>(INPUT);
+        let report = report(&left, &right);
+
+        assert_eq!(report.pairs[5], PairRow { left: 4, right: 9, difference: 5 });
+        assert_eq!(report.similarity[2], SimilarityRow { value: 3, occurrences: 3, contribution: 9 });
+        assert_eq!(report.summary.total_distance, 11);
+        assert_eq!(report.summary.similarity_score, 31);
+        assert_eq!(report.summary.median_gap, 1.5);
+        assert_eq!(report.summary.max_gap, 5);
+        assert_eq!(
+            report.summary.histogram,
+            vec![
+                Bucket { low: 0, high: 0, count: 1 },
+                Bucket { low: 1, high: 1, count: 2 },
+                Bucket { low: 2, high: 3, count: 2 },
+                Bucket { low: 4, high: 7, count: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wide_values() {
+        // the u32 version overflowed on both the contribution and the sums
+        let (left, right) = parse_input::<u32>("4000000000   4000000000\n4000000000   0");
+        let wide = report(&left, &right);
+        assert_eq!(wide.similarity[1], SimilarityRow { value: 4000000000, occurrences: 1, contribution: 4000000000 });
+        assert_eq!(wide.summary.similarity_score, 8000000000);
+
+        let (left, right) = parse_input::<i64>("-5000000000   5000000000\n3   -3");
+        let signed = report(&left, &right);
+        assert_eq!(signed.pairs[0], PairRow { left: -5000000000, right: -3, difference: 4999999997 });
+        assert_eq!(signed.summary.total_distance, 4999999997 + 4999999997);
+        assert_eq!(signed.summary.histogram[0], Bucket { low: 1 << 32, high: (1 << 33) - 1, count: 2 });
+    }
+
+    #[test]
+    fn test_export() {
+        let report = report::<u32>(&[1, 3], &[3, 3]);
+
+        assert_eq!(report.pairs_csv(), "left,right,difference\n1,3,2\n3,3,0\n");
+        assert_eq!(report.similarity_csv(), "value,occurrences,contribution\n1,0,0\n3,2,6\n");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json() {
+        let small = report::<u32>(&[1, 3], &[3, 3]);
+        assert_eq!(
+            small.to_json(),
+            r#"{"pairs":[{"left":1,"right":3,"difference":2},{"left":3,"right":3,"difference":0}],"similarity":[{"value":1,"occurrences":0,"contribution":0},{"value":3,"occurrences":2,"contribution":6}],"summary":{"total_distance":2,"similarity_score":6,"median_gap":1.0,"max_gap":2,"histogram":[{"low":0,"high":0,"count":1},{"low":2,"high":3,"count":1}]}}"#
+        );
+
+        // past what fits a u64, the JSON still holds the exact numbers
+        let extreme = report::<i128>(&[i128::MIN], &[i128::MAX]);
+        assert!(extreme.to_json().contains(&format!(r#""total_distance":{},"similarity_score":0"#, u128::MAX)));
+    }
+}
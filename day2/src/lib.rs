@@ -0,0 +1,359 @@
+use std::fmt;
+
+use aoc_config::parse_key_values;
+
+pub mod minimum;
+pub mod monitor;
+pub mod parallel;
+pub mod repair;
+pub mod windows;
+
+#[cfg(test)]
+mod rng;
+
+pub fn parse_report(line: &str) -> Vec<u32> {
+    line.split_ascii_whitespace().map(|x| x.parse().unwrap()).collect()
+}
+
+// the reverse of parse_report
+pub fn format_levels(levels: &[u32]) -> String {
+    levels.iter().map(|level| level.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+pub fn parse_input(input: &str) -> Vec<Vec<u32>> {
+    input.lines().map(parse_report).collect()
+}
+
+// What makes a report safe. The default is the puzzle's rule: levels strictly increasing or
+// strictly decreasing by 1 to 3 at each step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafetyPolicy {
+    pub min_step: u32,
+    pub max_step: u32,
+    // equal neighbours are a step of 0 that is neither up nor down, min_step does not apply
+    pub allow_equal: bool,
+    // whether every step has to go the same way
+    pub consistent_direction: bool,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        SafetyPolicy { min_step: 1, max_step: 3, allow_equal: false, consistent_direction: true }
+    }
+}
+
+impl SafetyPolicy {
+    // Reads a `key = value` file, settings that are not given keep their default.
+    pub fn from_config(config: &str) -> Result<SafetyPolicy, String> {
+        let mut policy = SafetyPolicy::default();
+        parse_key_values(config, |key, value| policy.set(key, value))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let invalid = || format!("invalid value for {}: {}", key, value);
+        match key {
+            "min_step" => self.min_step = value.parse().map_err(|_| invalid())?,
+            "max_step" => self.max_step = value.parse().map_err(|_| invalid())?,
+            "allow_equal" => self.allow_equal = value.parse().map_err(|_| invalid())?,
+            "consistent_direction" => self.consistent_direction = value.parse().map_err(|_| invalid())?,
+            _ => return Err(format!("unknown setting: {}", key)),
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_step > self.max_step {
+            return Err(format!("min_step {} is above max_step {}", self.min_step, self.max_step));
+        }
+        Ok(())
+    }
+
+    // `Some(true)` when the report has to be ascending, `None` when any direction goes
+    pub(crate) fn directions(&self) -> &'static [Option<bool>] {
+        if self.consistent_direction {
+            &[Some(true), Some(false)]
+        } else {
+            &[None]
+        }
+    }
+
+    pub(crate) fn allows_step(&self, a: u32, b: u32, ascending: Option<bool>) -> bool {
+        if a == b {
+            return self.allow_equal;
+        }
+        ascending.is_none_or(|ascending| (a < b) == ascending)
+            && (self.min_step..=self.max_step).contains(&a.abs_diff(b))
+    }
+}
+
+pub fn process(input: &str, policy: &SafetyPolicy, safety_check_fn: impl Fn(&[u32], &SafetyPolicy) -> bool) -> usize {
+    input
+        .lines()
+        .map(parse_report)
+        .filter(|x| safety_check_fn(x, policy))
+        .count()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsafeReason {
+    // each holds the index of the first level of the offending step
+    DirectionChange(usize),
+    StepTooLarge(usize),
+    StepTooSmall(usize),
+    FlatStep(usize),
+}
+
+impl fmt::Display for UnsafeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnsafeReason::DirectionChange(idx) => write!(f, "direction change at index {}", idx),
+            UnsafeReason::StepTooLarge(idx) => write!(f, "step too large at index {}", idx),
+            UnsafeReason::StepTooSmall(idx) => write!(f, "step too small at index {}", idx),
+            UnsafeReason::FlatStep(idx) => write!(f, "flat step at index {}", idx),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Safe,
+    // indexes of the levels to remove, as few as possible
+    SafeAfterRemoving(Vec<usize>),
+    Unsafe(UnsafeReason),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Safe => write!(f, "safe"),
+            Verdict::SafeAfterRemoving(removed) => {
+                let removed: Vec<String> = removed.iter().map(|idx| idx.to_string()).collect();
+                write!(f, "safe after removing index {}", removed.join(", "))
+            }
+            Verdict::Unsafe(reason) => write!(f, "unsafe, {}", reason),
+        }
+    }
+}
+
+// The first step that breaks the policy. A safe report can only go the way its first non-flat
+// step goes, so that step sets the direction when the policy wants a consistent one.
+pub fn check(report: &[u32], policy: &SafetyPolicy) -> Result<(), UnsafeReason> {
+    let ascending = match policy.consistent_direction {
+        true => report.windows(2).find(|pair| pair[0] != pair[1]).map(|pair| pair[0] < pair[1]),
+        false => None,
+    };
+
+    for (idx, pair) in report.windows(2).enumerate() {
+        let (a, b) = (pair[0], pair[1]);
+        if a == b {
+            if !policy.allow_equal {
+                return Err(UnsafeReason::FlatStep(idx));
+            }
+        } else if ascending.is_some_and(|ascending| (a < b) != ascending) {
+            return Err(UnsafeReason::DirectionChange(idx));
+        } else if a.abs_diff(b) > policy.max_step {
+            return Err(UnsafeReason::StepTooLarge(idx));
+        } else if a.abs_diff(b) < policy.min_step {
+            return Err(UnsafeReason::StepTooSmall(idx));
+        }
+    }
+    Ok(())
+}
+
+pub fn is_safe(report: &[u32], policy: &SafetyPolicy) -> bool {
+    check(report, policy).is_ok()
+}
+
+pub fn is_safe_with_removal(report: &[u32], policy: &SafetyPolicy) -> bool {
+    is_safe_with_removals(report, policy, 1)
+}
+
+pub fn is_safe_with_removals(report: &[u32], policy: &SafetyPolicy, max_removals: usize) -> bool {
+    removals(report, policy, max_removals).is_some()
+}
+
+// The fewest levels to remove, at most `max_removals`, to leave a safe report, in O(n * k).
+//
+// Safety only depends on neighbouring levels, so for a fixed direction (if the policy asks
+// for one) let fewest[i] be the fewest removals among the first i levels that leave a safe
+// report ending in level i. The level kept before i must then be one of the k + 1 levels
+// right before it, as anything further back already needs more than k removals:
+//
+//     fewest[i] = min(i, fewest[j] + (i - j - 1)) for i - k - 1 <= j < i with j -> i a safe step
+//
+// and the report can be made safe if fewest[i] plus the levels after i is at most k.
+pub fn removals(report: &[u32], policy: &SafetyPolicy, max_removals: usize) -> Option<Vec<usize>> {
+    let len = report.len();
+    if len == 0 {
+        return Some(Vec::new());
+    }
+    // removing more than every level means nothing, and keeps max_removals + 1 from overflowing
+    let max_removals = max_removals.min(len);
+
+    let mut best: Option<Vec<usize>> = None;
+    for &ascending in policy.directions() {
+        let mut fewest = vec![0; len];
+        // the level kept right before i, None when every level before i is removed
+        let mut previous = vec![None; len];
+
+        for i in 0..len {
+            fewest[i] = i;
+            for j in i.saturating_sub(max_removals + 1)..i {
+                if policy.allows_step(report[j], report[i], ascending) && fewest[j] + (i - j - 1) < fewest[i] {
+                    fewest[i] = fewest[j] + (i - j - 1);
+                    previous[i] = Some(j);
+                }
+            }
+        }
+
+        let last = (0..len).min_by_key(|&i| fewest[i] + (len - 1 - i)).unwrap();
+        if fewest[last] + (len - 1 - last) > max_removals {
+            continue;
+        }
+        let removed = removed_levels(len, last, &previous);
+        if best.as_ref().is_none_or(|best| removed.len() < best.len()) {
+            best = Some(removed);
+        }
+    }
+    best
+}
+
+// Follows `previous` back from the last kept level, every level not on the way is removed.
+pub(crate) fn removed_levels(len: usize, last: usize, previous: &[Option<usize>]) -> Vec<usize> {
+    let mut kept = vec![false; len];
+    let mut level = Some(last);
+    while let Some(idx) = level {
+        kept[idx] = true;
+        level = previous[idx];
+    }
+    (0..len).filter(|&idx| !kept[idx]).collect()
+}
+
+pub fn verdict(report: &[u32], policy: &SafetyPolicy, max_removals: usize) -> Verdict {
+    match check(report, policy) {
+        Ok(()) => Verdict::Safe,
+        Err(reason) => match removals(report, policy, max_removals) {
+            Some(removed) => Verdict::SafeAfterRemoving(removed),
+            None => Verdict::Unsafe(reason),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+
+    const INPUT: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
+    #[test]
+    fn test_part_one() {
+        let part_one = process(INPUT, &SafetyPolicy::default(), is_safe);
+        assert_eq!(part_one, 2);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let part_two = process(INPUT, &SafetyPolicy::default(), is_safe_with_removal);
+        assert_eq!(part_two, 4);
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let policy = SafetyPolicy::default();
+        let verdicts: Vec<Verdict> = parse_input(INPUT).iter().map(|report| verdict(report, &policy, 1)).collect();
+        assert_eq!(
+            verdicts,
+            vec![
+                Verdict::Safe,
+                Verdict::Unsafe(UnsafeReason::StepTooLarge(1)),
+                Verdict::Unsafe(UnsafeReason::StepTooLarge(2)),
+                Verdict::SafeAfterRemoving(vec![2]),
+                Verdict::SafeAfterRemoving(vec![3]),
+                Verdict::Safe,
+            ]
+        );
+        assert_eq!(verdict(&[1, 2, 7, 8, 9], &policy, 2), Verdict::SafeAfterRemoving(vec![0, 1]));
+        assert_eq!(check(&[5, 4, 6, 7], &policy), Err(UnsafeReason::DirectionChange(1)));
+        let policy = SafetyPolicy { min_step: 2, ..SafetyPolicy::default() };
+        assert_eq!(verdict(&[1, 3, 4, 6], &policy, 0), Verdict::Unsafe(UnsafeReason::StepTooSmall(1)));
+        assert_eq!(verdicts[3].to_string(), "safe after removing index 2");
+        assert_eq!(verdicts[1].to_string(), "unsafe, step too large at index 1");
+    }
+
+    #[test]
+    fn test_policy() {
+        let policy = SafetyPolicy::from_config("# wider steps, plateaus are fine
+max_step = 5
+allow_equal = true
+").unwrap();
+        assert_eq!(policy, SafetyPolicy { min_step: 1, max_step: 5, allow_equal: true, consistent_direction: true });
+        assert!(is_safe(&[1, 6, 6, 8], &policy));
+        assert!(!is_safe(&[1, 6, 6, 5], &policy));
+        assert_eq!(process(INPUT, &policy, is_safe), 5);
+
+        let policy = SafetyPolicy { consistent_direction: false, ..SafetyPolicy::default() };
+        assert!(is_safe(&[1, 3, 2, 4, 5], &policy));
+
+        assert_eq!(SafetyPolicy::from_config("min_step = 4"), Err("min_step 4 is above max_step 3".to_string()));
+        assert_eq!(SafetyPolicy::from_config("\nstep = 2"), Err("line 2: unknown setting: step".to_string()));
+    }
+
+    #[test]
+    fn test_more_removals() {
+        let policy = SafetyPolicy::default();
+        assert!(!is_safe_with_removals(&[1, 2, 7, 8, 9], &policy, 1));
+        assert!(is_safe_with_removals(&[1, 2, 7, 8, 9], &policy, 2));
+        assert!(is_safe_with_removals(&[9, 1, 8, 20, 7, 6], &policy, 2));
+        assert!(!is_safe_with_removals(&[9, 1, 8, 20, 7, 6], &policy, 1));
+        assert!(is_safe_with_removals(&[5, 5, 5], &policy, 2));
+        assert!(is_safe_with_removals(&[1, 2, 3], &policy, usize::MAX));
+        assert!(is_safe_with_removals(&[5, 5, 5], &policy, usize::MAX));
+        assert_eq!(removals(&[1, 2, 7, 8, 9], &policy, usize::MAX), Some(vec![0, 1]));
+        assert_eq!(process(INPUT, &policy, |report, policy| is_safe_with_removals(report, policy, 0)), 2);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = Lcg::new(3);
+
+        // every way of removing up to k levels, as a bitmask of the levels to keep
+        let brute_force = |report: &[u32], policy: &SafetyPolicy, max_removals: usize| {
+            (0u32..1 << report.len()).any(|keep| {
+                let kept: Vec<u32> = (0..report.len()).filter(|i| keep & (1 << i) != 0).map(|i| report[i]).collect();
+                report.len() - kept.len() <= max_removals && is_safe(&kept, policy)
+            })
+        };
+        let policies = [
+            SafetyPolicy::default(),
+            SafetyPolicy { min_step: 2, max_step: 4, allow_equal: true, consistent_direction: true },
+            SafetyPolicy { min_step: 1, max_step: 2, allow_equal: false, consistent_direction: false },
+        ];
+
+        for _ in 0..2000 {
+            let report = rng.report(9);
+            let len = report.len();
+            for (policy, max_removals) in policies.iter().flat_map(|policy| (0..3).map(move |k| (policy, k))) {
+                assert_eq!(
+                    is_safe_with_removals(&report, policy, max_removals),
+                    brute_force(&report, policy, max_removals),
+                    "{:?} {:?}",
+                    report,
+                    policy
+                );
+                if let Some(removed) = removals(&report, policy, max_removals) {
+                    let kept: Vec<u32> = (0..len).filter(|idx| !removed.contains(idx)).map(|idx| report[idx]).collect();
+                    assert!(is_safe(&kept, policy));
+                    assert!(removed.is_empty() || !brute_force(&report, policy, removed.len() - 1));
+                }
+            }
+        }
+    }
+}
@@ -1,64 +1,235 @@
-fn process(input: &str, safety_check_fn: fn(&Vec<u32>) -> bool) -> usize {
-    input
-        .lines()
-        .map(|x| x.split_ascii_whitespace().map(|x| x.parse().unwrap()).collect())
-        .filter(|x| safety_check_fn(x))
-        .count()
+use std::io::{self, Read};
+use std::process;
+use std::thread;
+
+use day2::minimum::removal_histogram;
+use day2::monitor::{Event, Monitor};
+use day2::parallel::{map_reports, process_parallel};
+use day2::repair::{repair, Objective, RepairError};
+use day2::windows::{analyze, summarize};
+use day2::{format_levels, is_safe, is_safe_with_removals, verdict, SafetyPolicy};
+
+const USAGE: &str = "usage:
+    day2 [explain|histogram|windows] [--threads N] [--max-removals K] [--policy PATH] [--min-step N] [--max-step N]
+         [--allow-equal true|false] [--consistent-direction true|false]
+    day2 monitor [--max-removals K] [policy flags as above]
+    day2 repair [--threads N] [--objective adjustment|changed] [policy flags as above]
+
+--threads spreads the reports over N threads, 0 for one per core, 1 by default; monitor reads
+stdin as it arrives and does not take it
+explain lists every report with its verdict and why it is unsafe
+repair suggests new values for every unsafe report, keeping either the total adjustment or the
+number of changed levels as small as possible (adjustment by default)
+monitor reads reports from stdin as they arrive and prints each verdict as soon as it is known
+windows lists the longest safe stretch and every maximal safe stretch of each report, as
+level index ranges, followed by a summary
+histogram counts reports by the fewest levels that have to be removed to make them safe
+part 2 counts reports that are safe after removing at most K levels, 1 by default
+the policy file holds `key = value` lines for min_step, max_step, allow_equal and
+consistent_direction, the other flags override it";
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
 }
 
-fn is_safe(report: &Vec<u32>) -> bool {
-    let check_ascending = report.is_sorted_by(|a, b| {a < b && a.abs_diff(*b) <= 3});
-    let check_descending = report.is_sorted_by(|a, b| {a > b && a.abs_diff(*b) <= 3});
-    check_ascending || check_descending
+struct Options {
+    // None when not given, so monitor can reject it
+    threads: Option<usize>,
+    max_removals: usize,
+    objective: Objective,
+    policy: SafetyPolicy,
 }
 
-fn is_safe_with_removal(report: &Vec<u32>) -> bool {
-    if is_safe(report) {
-        return true
+fn parse_options(args: &[String]) -> Options {
+    let mut options = Options { threads: None, max_removals: 1, objective: Objective::TotalAdjustment, policy: SafetyPolicy::default() };
+    let mut overrides = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let Some(value) = args.next() else {
+            fail(&format!("missing value for {}", flag));
+        };
+        match flag.as_str() {
+            "--threads" => {
+                options.threads = match value.parse() {
+                    Ok(0) => Some(thread::available_parallelism().unwrap().get()),
+                    Ok(threads) => Some(threads),
+                    Err(_) => fail(&format!("invalid number of threads: {}", value)),
+                }
+            }
+            "--max-removals" => {
+                options.max_removals =
+                    value.parse().unwrap_or_else(|_| fail(&format!("invalid number of removals: {}", value)))
+            }
+            "--objective" => {
+                options.objective = match value.as_str() {
+                    "adjustment" => Objective::TotalAdjustment,
+                    "changed" => Objective::ChangedLevels,
+                    _ => fail(&format!("unknown objective: {}", value)),
+                }
+            }
+            "--policy" => {
+                let config = std::fs::read_to_string(value).unwrap_or_else(|err| fail(&format!("{}: {}", value, err)));
+                options.policy =
+                    SafetyPolicy::from_config(&config).unwrap_or_else(|message| fail(&format!("{}: {}", value, message)));
+            }
+            "--min-step" | "--max-step" | "--allow-equal" | "--consistent-direction" => {
+                overrides.push((flag.trim_start_matches("--").replace('-', "_"), value))
+            }
+            _ => fail(&format!("unknown flag: {}", flag)),
+        }
+    }
+    // flags win over the policy file wherever they appear
+    for (key, value) in overrides {
+        options.policy.set(&key, value).unwrap_or_else(|message| fail(&message));
     }
+    options.policy.validate().unwrap_or_else(|message| fail(&message));
+    options
+}
 
-    for i in 0..report.len() {
-        let slice = report
-            .into_iter()
-            .enumerate()
-            .filter(|&(idx, _)| idx != i)
-            .map(|(_, &val)| val)
-            .collect();
+impl Options {
+    fn threads(&self) -> usize {
+        self.threads.unwrap_or(1)
+    }
+}
 
-        if is_safe(&slice) {
-            return true
+fn print_explanations(input: &str, options: &Options) {
+    let explanations = map_reports(input, options.threads(), |report| {
+        format!("{} => {}", format_levels(report), verdict(report, &options.policy, options.max_removals))
+    });
+    for (line, explanation) in explanations.iter().enumerate() {
+        println!("{}: {}", line + 1, explanation);
+    }
+}
+
+fn print_histogram(input: &str, options: &Options) {
+    println!("removals,reports");
+    for (removals, reports) in removal_histogram(input, &options.policy, options.threads()) {
+        println!("{},{}", removals, reports);
+    }
+}
+
+fn print_repairs(input: &str, options: &Options) {
+    // only unsafe reports are kept, already formatted, so the levels are not held twice
+    let repairs = map_reports(input, options.threads(), |report| {
+        (!is_safe(report, &options.policy)).then(|| (format_levels(report), repair(report, &options.policy, options.objective)))
+    });
+    for (line, result) in repairs.into_iter().enumerate() {
+        match result {
+            None => {}
+            Some((report, Ok(repaired))) => println!(
+                "{}: {} => {} (adjusted by {}, {} changed)",
+                line + 1,
+                report,
+                format_levels(&repaired.levels),
+                repaired.adjustment,
+                repaired.changed
+            ),
+            Some((report, Err(RepairError::Unsatisfiable))) => println!("{}: {} => no safe report under this policy", line + 1, report),
+            Some((report, Err(RepairError::TooManyCandidates))) => {
+                println!("{}: {} => too many candidate values to repair", line + 1, report)
+            }
         }
     }
-    false
 }
 
-fn main() {
-    let input = std::fs::read_to_string("./inputs/day2.txt").unwrap();
-    let part_one = process(&input, is_safe);
-    let part_two = process(&input, is_safe_with_removal);
-    println!("part 1: {}, part 2: {}", part_one, part_two);
+fn print_windows(input: &str, options: &Options) {
+    let analyses = map_reports(input, options.threads(), |report| analyze(report, &options.policy));
+    for (line, analysis) in analyses.iter().enumerate() {
+        let segments: Vec<String> = analysis.segments.iter().map(|segment| format!("{:?}", segment)).collect();
+        println!(
+            "{}: longest {:?} ({} of {} levels), segments {}",
+            line + 1,
+            analysis.longest,
+            analysis.longest.len(),
+            analysis.levels,
+            segments.join(" ")
+        );
+    }
+
+    let summary = summarize(&analyses);
+    println!("\nreports: {}", summary.reports);
+    println!("fully safe: {}", summary.fully_safe);
+    println!("average coverage of the longest window: {:.1}%", summary.average_coverage * 100.0);
+    if let Some((idx, longest)) = summary.longest {
+        println!("longest window: {:?} on line {} ({} levels)", longest, idx + 1, longest.len());
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9";
-
-    #[test]
-    fn test_part_one() {
-        let part_one = process(INPUT, is_safe);
-        assert_eq!(part_one, 2);
+fn print_event(event: Event) {
+    match event {
+        Event::NeedsDampener { report, level } => {
+            println!("report {}: not safe as is from level {}, trying the dampener", report + 1, level + 1)
+        }
+        Event::Malformed { report, level } => {
+            println!("report {}: level {} is not a valid level, skipping the report", report + 1, level + 1)
+        }
+        Event::Verdict { report, outcome, levels, early, counts } => {
+            println!(
+                "report {}: {} after {} levels{} (safe {}, dampener-safe {}, unsafe {})",
+                report + 1,
+                outcome,
+                levels,
+                if early { ", before it ended" } else { "" },
+                counts.safe,
+                counts.dampener_safe,
+                counts.unsafe_reports
+            );
+        }
     }
+}
+
+// Reads stdin as it comes in rather than line by line, so levels are judged while the rest of
+// their report is still on the way.
+fn run_monitor(options: &Options) {
+    let mut monitor = Monitor::new(options.policy.clone(), options.max_removals);
+    let mut stdin = io::stdin().lock();
+    let mut buffer = [0; 4096];
 
-    #[test]
-    fn test_part_two() {
-        let part_two = process(INPUT, is_safe_with_removal);
-        assert_eq!(part_two, 4);
+    loop {
+        let read = stdin.read(&mut buffer).unwrap();
+        let events = if read == 0 { monitor.finish() } else { monitor.feed(&buffer[..read]) };
+        for event in events {
+            print_event(event);
+        }
+        if read == 0 {
+            break;
+        }
     }
-}
\ No newline at end of file
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, flags) = match args.first().map(String::as_str) {
+        Some(command @ ("explain" | "histogram" | "monitor" | "repair" | "windows")) => (Some(command), &args[1..]),
+        _ => (None, &args[..]),
+    };
+    let options = parse_options(flags);
+    if command == Some("monitor") {
+        if options.threads.is_some() {
+            fail("monitor does not take --threads");
+        }
+        run_monitor(&options);
+        return;
+    }
+
+    let input = std::fs::read_to_string("./inputs/day2.txt").unwrap();
+    match command {
+        Some("explain") => print_explanations(&input, &options),
+        Some("histogram") => print_histogram(&input, &options),
+        Some("repair") => print_repairs(&input, &options),
+        Some("windows") => print_windows(&input, &options),
+        _ => {
+            let max_removals = options.max_removals;
+            let part_one = process_parallel(&input, &options.policy, is_safe, options.threads());
+            let part_two = process_parallel(
+                &input,
+                &options.policy,
+                |report, policy| is_safe_with_removals(report, policy, max_removals),
+                options.threads(),
+            );
+            println!("part 1: {}, part 2: {}", part_one, part_two);
+        }
+    }
+}
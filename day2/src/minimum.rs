@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use crate::parallel::map_reports;
+use crate::{removed_levels, SafetyPolicy};
+
+// The fewest levels to drop so a report is safe, with no upper bound. A segment tree over
+// values finds the best earlier level for each one, O(n log n) per report.
+
+// range minimum over values, each slot holding (fewest[j] - j, j)
+struct MinTree {
+    size: usize,
+    nodes: Vec<Option<(i64, usize)>>,
+}
+
+impl MinTree {
+    fn new(len: usize) -> Self {
+        let size = len.next_power_of_two();
+        MinTree { size, nodes: vec![None; 2 * size] }
+    }
+
+    fn insert(&mut self, slot: usize, entry: (i64, usize)) {
+        let mut node = slot + self.size;
+        while node > 0 {
+            self.nodes[node] = Some(self.nodes[node].map_or(entry, |current| current.min(entry)));
+            node /= 2;
+        }
+    }
+
+    // minimum over the slots in [start, end)
+    fn query(&self, start: usize, end: usize) -> Option<(i64, usize)> {
+        let (mut start, mut end) = (start + self.size, end + self.size);
+        let mut best: Option<(i64, usize)> = None;
+        let mut take = |entry: Option<(i64, usize)>| {
+            if let Some(entry) = entry {
+                best = Some(best.map_or(entry, |best| best.min(entry)));
+            }
+        };
+        while start < end {
+            if start % 2 == 1 {
+                take(self.nodes[start]);
+                start += 1;
+            }
+            if end % 2 == 1 {
+                end -= 1;
+                take(self.nodes[end]);
+            }
+            start /= 2;
+            end /= 2;
+        }
+        best
+    }
+}
+
+// Inclusive ranges of the values a level can follow on to reach `value`.
+fn predecessor_ranges(policy: &SafetyPolicy, value: u32, ascending: Option<bool>) -> Vec<(u32, u32)> {
+    let mut ranges = Vec::new();
+    // equal values are only ever allowed through allow_equal
+    let min_step = policy.min_step.max(1);
+    if min_step <= policy.max_step {
+        if ascending != Some(false) && value >= min_step {
+            ranges.push((value.saturating_sub(policy.max_step), value - min_step));
+        }
+        if ascending != Some(true) && value.checked_add(min_step).is_some() {
+            ranges.push((value + min_step, value.saturating_add(policy.max_step)));
+        }
+    }
+    if policy.allow_equal {
+        ranges.push((value, value));
+    }
+    ranges
+}
+
+pub fn minimum_removals(report: &[u32], policy: &SafetyPolicy) -> Vec<usize> {
+    let len = report.len();
+    if len == 0 {
+        return Vec::new();
+    }
+
+    let mut values = report.to_vec();
+    values.sort();
+    values.dedup();
+    let slot = |value: u32| values.partition_point(|&x| x < value);
+    let slots = |low: u32, high: u32| (slot(low), values.partition_point(|&x| x <= high));
+
+    let mut best: Option<Vec<usize>> = None;
+    for &ascending in policy.directions() {
+        let mut tree = MinTree::new(values.len());
+        let mut fewest = vec![0; len];
+        let mut previous = vec![None; len];
+
+        for (i, &value) in report.iter().enumerate() {
+            fewest[i] = i;
+            for (low, high) in predecessor_ranges(policy, value, ascending) {
+                let (start, end) = slots(low, high);
+                let Some((score, j)) = tree.query(start, end) else {
+                    continue;
+                };
+                let candidate = (score + i as i64 - 1) as usize;
+                if candidate < fewest[i] {
+                    fewest[i] = candidate;
+                    previous[i] = Some(j);
+                }
+            }
+            tree.insert(slot(value), (fewest[i] as i64 - i as i64, i));
+        }
+
+        let last = (0..len).min_by_key(|&i| fewest[i] + (len - 1 - i)).unwrap();
+        let removed = removed_levels(len, last, &previous);
+        if best.as_ref().is_none_or(|best| removed.len() < best.len()) {
+            best = Some(removed);
+        }
+    }
+    best.unwrap()
+}
+
+// How many reports need each number of removals.
+pub fn removal_histogram(input: &str, policy: &SafetyPolicy, threads: usize) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+    for removals in map_reports(input, threads, |report| minimum_removals(report, policy).len()) {
+        *histogram.entry(removals).or_default() += 1;
+    }
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+    use crate::{is_safe, removals};
+
+    #[test]
+    fn test_histogram() {
+        let input = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+        let policy = SafetyPolicy::default();
+        assert_eq!(minimum_removals(&[1, 2, 7, 8, 9], &policy), vec![0, 1]);
+        assert_eq!(minimum_removals(&[9, 1, 8, 20, 7, 6, 30, 5], &policy), vec![1, 3, 6]);
+        assert_eq!(removal_histogram(input, &policy, 1), BTreeMap::from([(0, 2), (1, 2), (2, 2)]));
+        assert_eq!(removal_histogram(input, &policy, 4), BTreeMap::from([(0, 2), (1, 2), (2, 2)]));
+    }
+
+    #[test]
+    fn test_matches_quadratic() {
+        let mut rng = Lcg::new(5);
+        let policies = [
+            SafetyPolicy::default(),
+            SafetyPolicy { min_step: 0, max_step: 4, allow_equal: true, consistent_direction: true },
+            SafetyPolicy { min_step: 2, max_step: 3, allow_equal: false, consistent_direction: false },
+        ];
+
+        for _ in 0..500 {
+            let len = rng.below(40) as usize;
+            let report: Vec<u32> = (0..len).map(|_| rng.below(25) as u32).collect();
+            for policy in &policies {
+                let removed = minimum_removals(&report, policy);
+                let kept: Vec<u32> = (0..len).filter(|idx| !removed.contains(idx)).map(|idx| report[idx]).collect();
+                assert!(is_safe(&kept, policy), "{:?} {:?}", report, policy);
+                assert_eq!(removed.len(), removals(&report, policy, len).unwrap().len(), "{:?} {:?}", report, policy);
+            }
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::SafetyPolicy;
+
+// Judges reports while their levels are still arriving, running the `removals` recurrence
+// one level at a time so a report is called unsafe as soon as no later level can save it.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Safe,
+    DampenerSafe,
+    Unsafe,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Safe => write!(f, "safe"),
+            Outcome::DampenerSafe => write!(f, "dampener-safe"),
+            Outcome::Unsafe => write!(f, "unsafe"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    // the report stopped being safe as is with this level (counting from 0), the dampener
+    // may still save it
+    NeedsDampener { report: usize, level: usize },
+    // the level is not a number that fits a u32, the rest of the report is skipped and it gets
+    // no verdict
+    Malformed { report: usize, level: usize },
+    // `levels` is how many levels had arrived, `early` when the report had not ended yet and
+    // `counts` the running totals including this verdict
+    Verdict { report: usize, outcome: Outcome, levels: usize, early: bool, counts: Counts },
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Counts {
+    pub safe: usize,
+    pub dampener_safe: usize,
+    pub unsafe_reports: usize,
+}
+
+struct Kept {
+    level: u32,
+    index: usize,
+    // the fewest removals before this level that leave a safe report ending in it
+    fewest: usize,
+}
+
+struct Tracker {
+    max_removals: usize,
+    // per direction, the last k + 1 levels
+    windows: Vec<(Option<bool>, VecDeque<Kept>)>,
+    alive: bool,
+}
+
+impl Tracker {
+    fn new(policy: &SafetyPolicy, max_removals: usize) -> Self {
+        let windows = policy.directions().iter().map(|&ascending| (ascending, VecDeque::new())).collect();
+        Tracker { max_removals, windows, alive: true }
+    }
+
+    fn push(&mut self, policy: &SafetyPolicy, level: u32, index: usize) {
+        let mut alive = false;
+        for (ascending, window) in &mut self.windows {
+            let fewest = window
+                .iter()
+                .filter(|kept| policy.allows_step(kept.level, level, *ascending))
+                .map(|kept| kept.fewest + (index - kept.index - 1))
+                .fold(index, usize::min);
+
+            window.push_back(Kept { level, index, fewest });
+            if window.len() > self.max_removals.saturating_add(1) {
+                window.pop_front();
+            }
+            alive |= window.iter().any(|kept| kept.fewest + (index - kept.index) <= self.max_removals);
+        }
+        self.alive = alive;
+    }
+}
+
+struct LiveReport {
+    levels: usize,
+    plain: Tracker,
+    dampened: Tracker,
+    decided: bool,
+}
+
+impl LiveReport {
+    fn new(policy: &SafetyPolicy, max_removals: usize) -> Self {
+        LiveReport {
+            levels: 0,
+            plain: Tracker::new(policy, 0),
+            dampened: Tracker::new(policy, max_removals),
+            decided: false,
+        }
+    }
+}
+
+// longer than any u32, there is no point holding on to more of a level
+const MAX_LEVEL_LEN: usize = 16;
+
+pub struct Monitor {
+    policy: SafetyPolicy,
+    max_removals: usize,
+    reports: usize,
+    current: Option<LiveReport>,
+    // the digits of a level that has not been terminated yet
+    partial: String,
+    // whether anything but the newline has arrived on the current line, like `str::lines` a
+    // last line without a newline still counts
+    line_started: bool,
+    counts: Counts,
+}
+
+impl Monitor {
+    pub fn new(policy: SafetyPolicy, max_removals: usize) -> Self {
+        Monitor {
+            policy,
+            max_removals,
+            reports: 0,
+            current: None,
+            partial: String::new(),
+            line_started: false,
+            counts: Counts::default(),
+        }
+    }
+
+    pub fn counts(&self) -> Counts {
+        self.counts
+    }
+
+    // Takes input in chunks of any size, a level or a report may be split across chunks. A
+    // blank line is an empty report, which is safe, the same as for `process`.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+        for &byte in bytes {
+            if byte == b'\n' {
+                self.end_level(&mut events);
+                self.end_report(&mut events);
+                continue;
+            }
+            self.line_started = true;
+            if byte.is_ascii_whitespace() {
+                self.end_level(&mut events);
+            } else if self.partial.len() < MAX_LEVEL_LEN {
+                self.partial.push(byte as char);
+            }
+        }
+        events
+    }
+
+    // Ends the last report if the input did not end in a newline.
+    pub fn finish(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        self.end_level(&mut events);
+        if self.line_started {
+            self.end_report(&mut events);
+        }
+        events
+    }
+
+    fn end_level(&mut self, events: &mut Vec<Event>) {
+        if self.partial.is_empty() {
+            return;
+        }
+        let level = self.partial.parse::<u32>();
+        self.partial.clear();
+
+        let report = self.current.get_or_insert_with(|| LiveReport::new(&self.policy, self.max_removals));
+        let index = report.levels;
+        report.levels += 1;
+        if report.decided {
+            return;
+        }
+        let Ok(level) = level else {
+            // decided without a verdict, so it is never counted
+            report.decided = true;
+            events.push(Event::Malformed { report: self.reports, level: index });
+            return;
+        };
+
+        let was_safe = report.plain.alive;
+        report.plain.push(&self.policy, level, index);
+        report.dampened.push(&self.policy, level, index);
+
+        if !report.dampened.alive {
+            report.decided = true;
+            self.counts.unsafe_reports += 1;
+            events.push(Event::Verdict {
+                report: self.reports,
+                outcome: Outcome::Unsafe,
+                levels: index + 1,
+                early: true,
+                counts: self.counts,
+            });
+        } else if was_safe && !report.plain.alive {
+            events.push(Event::NeedsDampener { report: self.reports, level: index });
+        }
+    }
+
+    fn end_report(&mut self, events: &mut Vec<Event>) {
+        self.line_started = false;
+        let report = self.current.take().unwrap_or_else(|| LiveReport::new(&self.policy, self.max_removals));
+        if !report.decided {
+            let outcome = if report.plain.alive { Outcome::Safe } else { Outcome::DampenerSafe };
+            match outcome {
+                Outcome::Safe => self.counts.safe += 1,
+                _ => self.counts.dampener_safe += 1,
+            }
+            events.push(Event::Verdict { report: self.reports, outcome, levels: report.levels, early: false, counts: self.counts });
+        }
+        self.reports += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+    use crate::{format_levels, is_safe, is_safe_with_removals, parse_input};
+
+    const INPUT: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
+    #[test]
+    fn test_early_verdicts() {
+        let mut monitor = Monitor::new(SafetyPolicy::default(), 1);
+
+        // the report is cut off mid level, nothing can be said about it yet
+        assert_eq!(monitor.feed(b"1 2 7 1"), vec![Event::NeedsDampener { report: 0, level: 2 }]);
+        // keeping 1 2 or 7 10 takes two removals already, whatever comes next
+        let counts = Counts { safe: 0, dampener_safe: 0, unsafe_reports: 1 };
+        assert_eq!(monitor.feed(b"0 "), vec![Event::Verdict { report: 0, outcome: Outcome::Unsafe, levels: 4, early: true, counts }]);
+
+        let counts = Counts { safe: 1, dampener_safe: 0, unsafe_reports: 1 };
+        assert_eq!(monitor.feed(b"9 8\n7 6 4 2 1\n1 3 2 4 5"), vec![
+            Event::Verdict { report: 1, outcome: Outcome::Safe, levels: 5, early: false, counts },
+            Event::NeedsDampener { report: 2, level: 2 },
+        ]);
+
+        let counts = Counts { safe: 1, dampener_safe: 1, unsafe_reports: 1 };
+        assert_eq!(monitor.finish(), vec![Event::Verdict { report: 2, outcome: Outcome::DampenerSafe, levels: 5, early: false, counts }]);
+        assert_eq!(monitor.counts(), counts);
+
+        // any number of removals is allowed, even one past what a usize holds
+        let mut monitor = Monitor::new(SafetyPolicy::default(), usize::MAX);
+        let counts = Counts { safe: 1, dampener_safe: 0, unsafe_reports: 0 };
+        assert_eq!(monitor.feed(b"1 2 3\n"), vec![Event::Verdict { report: 0, outcome: Outcome::Safe, levels: 3, early: false, counts }]);
+        let counts = Counts { safe: 1, dampener_safe: 1, unsafe_reports: 0 };
+        assert_eq!(monitor.feed(b"1 9 2 30 3\n"), vec![
+            Event::NeedsDampener { report: 1, level: 1 },
+            Event::Verdict { report: 1, outcome: Outcome::DampenerSafe, levels: 5, early: false, counts },
+        ]);
+    }
+
+    #[test]
+    fn test_blank_and_malformed() {
+        let mut monitor = Monitor::new(SafetyPolicy::default(), 1);
+        let mut events = monitor.feed(b"1 2 3\n\n4 x 5\n9 8 7\n5 99999999999 1\n  ");
+        events.extend(monitor.finish());
+
+        let counts = |safe| Counts { safe, dampener_safe: 0, unsafe_reports: 0 };
+        assert_eq!(events, vec![
+            Event::Verdict { report: 0, outcome: Outcome::Safe, levels: 3, early: false, counts: counts(1) },
+            Event::Verdict { report: 1, outcome: Outcome::Safe, levels: 0, early: false, counts: counts(2) },
+            Event::Malformed { report: 2, level: 1 },
+            Event::Verdict { report: 3, outcome: Outcome::Safe, levels: 3, early: false, counts: counts(3) },
+            Event::Malformed { report: 4, level: 1 },
+            Event::Verdict { report: 5, outcome: Outcome::Safe, levels: 0, early: false, counts: counts(4) },
+        ]);
+        // the same count as the batch path, which also takes blank lines as empty reports
+        assert_eq!(crate::process("1 2 3\n\n9 8 7\n  ", &SafetyPolicy::default(), is_safe), 4);
+    }
+
+    #[test]
+    fn test_matches_batch() {
+        let mut rng = Lcg::new(13);
+        let mut input = String::from(INPUT);
+        for _ in 0..300 {
+            input += &format!("\n{}", format_levels(&rng.report(10)));
+            if rng.below(40) == 0 {
+                input += "\n";
+            }
+        }
+
+        for max_removals in 0..3 {
+            let policy = SafetyPolicy::default();
+            let mut monitor = Monitor::new(policy.clone(), max_removals);
+            let mut verdicts = Vec::new();
+            // odd sized chunks so levels get split
+            for chunk in input.as_bytes().chunks(7) {
+                verdicts.extend(monitor.feed(chunk));
+            }
+            verdicts.extend(monitor.finish());
+
+            let outcomes: Vec<Outcome> = verdicts
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Verdict { outcome, .. } => Some(*outcome),
+                    _ => None,
+                })
+                .collect();
+            let expected: Vec<Outcome> = parse_input(&input)
+                .iter()
+                .map(|report| match (is_safe(report, &policy), is_safe_with_removals(report, &policy, max_removals)) {
+                    (true, _) => Outcome::Safe,
+                    (false, true) => Outcome::DampenerSafe,
+                    (false, false) => Outcome::Unsafe,
+                })
+                .collect();
+            assert_eq!(outcomes, expected);
+        }
+    }
+}
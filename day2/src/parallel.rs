@@ -0,0 +1,86 @@
+use std::thread;
+
+use crate::{parse_report, SafetyPolicy};
+
+// Spreads reports over scoped threads. The input is cut into one slice of whole lines per
+// thread, each thread works through its slice in order and the results are put back together
+// in slice order, so counts and per-report output come out the same as on a single thread.
+
+// Cuts the input into at most `parts` slices of roughly equal size, only ever after a newline.
+fn split_lines(input: &str, parts: usize) -> Vec<&str> {
+    let bytes = input.as_bytes();
+    let target = input.len().div_ceil(parts.max(1)).max(1);
+    let mut slices = Vec::new();
+    let mut start = 0;
+
+    while start < input.len() {
+        let end = match bytes[(start + target).min(input.len())..].iter().position(|&byte| byte == b'\n') {
+            Some(offset) => (start + target).min(input.len()) + offset + 1,
+            None => input.len(),
+        };
+        slices.push(&input[start..end]);
+        start = end;
+    }
+    slices
+}
+
+// One result per line of the input, in input order.
+pub fn map_reports<T: Send>(input: &str, threads: usize, f: impl Fn(&[u32]) -> T + Sync) -> Vec<T> {
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = split_lines(input, threads)
+            .into_iter()
+            .map(|slice| scope.spawn(move || slice.lines().map(|line| f(&parse_report(line))).collect::<Vec<T>>()))
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+// `process` on several threads, without collecting anything per report.
+pub fn process_parallel(
+    input: &str,
+    policy: &SafetyPolicy,
+    safety_check_fn: impl Fn(&[u32], &SafetyPolicy) -> bool + Sync,
+    threads: usize,
+) -> usize {
+    let safety_check_fn = &safety_check_fn;
+    thread::scope(|scope| {
+        let handles: Vec<_> = split_lines(input, threads)
+            .into_iter()
+            .map(|slice| scope.spawn(move || slice.lines().map(parse_report).filter(|x| safety_check_fn(x, policy)).count()))
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).sum()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+    use crate::{format_levels, is_safe, is_safe_with_removal, process, verdict};
+
+    #[test]
+    fn test_split_lines() {
+        assert_eq!(split_lines("1 2\n3 4\n5 6\n7 8", 2), vec!["1 2\n3 4\n5 6\n", "7 8"]);
+        assert_eq!(split_lines("1 2\n3 4", 8), vec!["1 2\n", "3 4"]);
+        assert_eq!(split_lines("", 4), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_matches_sequential() {
+        let mut rng = Lcg::new(17);
+        let input: Vec<String> = (0..2000).map(|_| format_levels(&rng.report(8))).collect();
+        let input = input.join("\n");
+        let policy = SafetyPolicy::default();
+
+        let verdicts: Vec<_> = input.lines().map(|line| verdict(&parse_report(line), &policy, 1)).collect();
+        for threads in [1, 3, 8] {
+            assert_eq!(process_parallel(&input, &policy, is_safe, threads), process(&input, &policy, is_safe));
+            assert_eq!(
+                process_parallel(&input, &policy, is_safe_with_removal, threads),
+                process(&input, &policy, is_safe_with_removal)
+            );
+            assert_eq!(map_reports(&input, threads, |report| verdict(report, &policy, 1)), verdicts);
+        }
+    }
+}
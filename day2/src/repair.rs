@@ -0,0 +1,289 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use crate::SafetyPolicy;
+
+// Repairs an unsafe report by changing values, with a DP over candidate values per level
+// that takes the cheapest predecessor from sliding windows over the allowed steps.
+
+// More candidate values than this and a report is not repaired at all.
+const MAX_CANDIDATES: u64 = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    // the sum of how far each level moved
+    TotalAdjustment,
+    // how many levels changed, ties broken by the total adjustment
+    ChangedLevels,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairError {
+    // no safe report exists under the policy
+    Unsatisfiable,
+    TooManyCandidates,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Repair {
+    pub levels: Vec<u32>,
+    pub adjustment: u64,
+    pub changed: usize,
+}
+
+// For every v, the u with values[v] - values[u] in [low, high] and the lowest cost, if any
+// is reachable. `values` is sorted, so the window only ever slides forwards.
+fn window_minimum(values: &[u64], costs: &[u64], low: i64, high: i64) -> Vec<Option<usize>> {
+    let mut minimum = vec![None; costs.len()];
+    let mut window: VecDeque<usize> = VecDeque::new();
+    let mut next = 0;
+
+    for (v, minimum) in minimum.iter_mut().enumerate() {
+        let (start, end) = (values[v] as i64 - high, values[v] as i64 - low);
+        while next < costs.len() && values[next] as i64 <= end {
+            if costs[next] != u64::MAX {
+                while window.back().is_some_and(|&back| costs[back] >= costs[next]) {
+                    window.pop_back();
+                }
+                window.push_back(next);
+            }
+            next += 1;
+        }
+        while window.front().is_some_and(|&front| (values[front] as i64) < start) {
+            window.pop_front();
+        }
+        *minimum = window.front().copied();
+    }
+    minimum
+}
+
+// The allowed differences between neighbours as inclusive ranges.
+fn step_ranges(policy: &SafetyPolicy, ascending: Option<bool>) -> Vec<(i64, i64)> {
+    let mut ranges = Vec::new();
+    let (min_step, max_step) = (policy.min_step.max(1) as i64, policy.max_step as i64);
+    if min_step <= max_step {
+        if ascending != Some(false) {
+            ranges.push((min_step, max_step));
+        }
+        if ascending != Some(true) {
+            ranges.push((-max_step, -min_step));
+        }
+    }
+    if policy.allow_equal {
+        ranges.push((0, 0));
+    }
+    ranges
+}
+
+// Every value within (len - 1) * max_step of the report's values. A safe report spans at
+// most that much, so one lying entirely outside could be shifted closer for less.
+fn contiguous(report: &[u32], policy: &SafetyPolicy) -> (u64, u64) {
+    let span = (report.len() as u64 - 1) * policy.max_step as u64;
+    let lowest = (*report.iter().min().unwrap() as u64).saturating_sub(span);
+    let highest = (*report.iter().max().unwrap() as u64 + span).min(u32::MAX as u64);
+    (lowest, highest)
+}
+
+// An optimal repair only uses values that are a report value (or 0 or u32::MAX) plus a sum of
+// at most len - 1 step bounds, since each level is either left alone, pinned to a bound or
+// chained to such a level by tight steps. None if there are more than `limit` of them.
+fn breakpoints(report: &[u32], policy: &SafetyPolicy, limit: u64) -> Option<Vec<u64>> {
+    let (min_step, max_step) = (policy.min_step.max(1) as i64, policy.max_step as i64);
+    let n = report.len() as u64 - 1;
+    let count = match min_step.cmp(&max_step) {
+        Ordering::Greater => 1,
+        Ordering::Equal => 2 * n + 1,
+        Ordering::Less => n.saturating_mul(2 * n + 2).saturating_add(1),
+    };
+    if (n + 3).saturating_mul(count) > limit {
+        return None;
+    }
+
+    let n = n as i64;
+    let offsets: Vec<i64> = match min_step.cmp(&max_step) {
+        Ordering::Greater => vec![0],
+        Ordering::Equal => (-n..=n).map(|p| p * min_step).collect(),
+        Ordering::Less => (-n..=n).flat_map(|p| (p.abs() - n..=n - p.abs()).map(move |q| p * min_step + q * max_step)).collect(),
+    };
+
+    let bases = report.iter().map(|&level| level as i64).chain([0, u32::MAX as i64]);
+    let mut values: Vec<u64> = bases
+        .flat_map(|base| offsets.iter().map(move |offset| base + offset))
+        .filter(|&value| (0..=u32::MAX as i64).contains(&value))
+        .map(|value| value as u64)
+        .collect();
+    values.sort_unstable();
+    values.dedup();
+    Some(values)
+}
+
+pub fn repair(report: &[u32], policy: &SafetyPolicy, objective: Objective) -> Result<Repair, RepairError> {
+    if report.is_empty() {
+        return Ok(Repair { levels: Vec::new(), adjustment: 0, changed: 0 });
+    }
+    // whichever candidate set is smaller, both give the same optimum
+    let (lowest, highest) = contiguous(report, policy);
+    let values = match breakpoints(report, policy, (highest - lowest + 1).min(MAX_CANDIDATES)) {
+        Some(values) => values,
+        None if highest - lowest < MAX_CANDIDATES => (lowest..=highest).collect(),
+        None => return Err(RepairError::TooManyCandidates),
+    };
+    repair_over(report, policy, objective, &values).ok_or(RepairError::Unsatisfiable)
+}
+
+fn repair_over(report: &[u32], policy: &SafetyPolicy, objective: Objective, values: &[u64]) -> Option<Repair> {
+    let len = report.len();
+    // a changed level costs more than any possible sum of adjustments
+    let change_cost = len as u64 * (values[values.len() - 1] - values[0] + 1);
+    let cost = |level: u32, v: usize| {
+        let adjustment = (level as u64).abs_diff(values[v]);
+        match objective {
+            Objective::TotalAdjustment => adjustment,
+            Objective::ChangedLevels if adjustment == 0 => 0,
+            Objective::ChangedLevels => change_cost + adjustment,
+        }
+    };
+
+    let mut best: Option<(u64, Vec<u32>)> = None;
+    for &ascending in policy.directions() {
+        let ranges = step_ranges(policy, ascending);
+        let mut costs: Vec<u64> = (0..values.len()).map(|v| cost(report[0], v)).collect();
+        // previous[i][v], the value level i - 1 had on the cheapest way to v
+        let mut previous: Vec<Vec<usize>> = Vec::with_capacity(len);
+
+        for &level in &report[1..] {
+            let minimums: Vec<Vec<Option<usize>>> =
+                ranges.iter().map(|&(low, high)| window_minimum(values, &costs, low, high)).collect();
+            let mut next_costs = vec![u64::MAX; values.len()];
+            let mut from = vec![0; values.len()];
+
+            for v in 0..values.len() {
+                let Some(u) = minimums.iter().filter_map(|minimum| minimum[v]).min_by_key(|&u| costs[u]) else {
+                    continue;
+                };
+                next_costs[v] = costs[u] + cost(level, v);
+                from[v] = u;
+            }
+            costs = next_costs;
+            previous.push(from);
+        }
+
+        let last = (0..values.len()).filter(|&v| costs[v] != u64::MAX).min_by_key(|&v| costs[v]);
+        let Some(mut v) = last else {
+            continue;
+        };
+        if best.as_ref().is_some_and(|(best, _)| *best <= costs[v]) {
+            continue;
+        }
+        let total = costs[v];
+        let mut levels = vec![values[v] as u32];
+        for from in previous.iter().rev() {
+            v = from[v];
+            levels.push(values[v] as u32);
+        }
+        levels.reverse();
+        best = Some((total, levels));
+    }
+
+    let (_, levels) = best?;
+    Some(Repair {
+        adjustment: levels.iter().zip(report).map(|(a, b)| a.abs_diff(*b) as u64).sum(),
+        changed: levels.iter().zip(report).filter(|(a, b)| a != b).count(),
+        levels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+    use crate::is_safe;
+
+    #[test]
+    fn test_repair() {
+        let policy = SafetyPolicy::default();
+
+        let repaired = repair(&[1, 2, 7, 8, 9], &policy, Objective::TotalAdjustment).unwrap();
+        assert_eq!(repaired.adjustment, 2);
+        assert!(is_safe(&repaired.levels, &policy));
+
+        let repaired = repair(&[8, 6, 4, 4, 1], &policy, Objective::TotalAdjustment).unwrap();
+        assert_eq!(repaired.adjustment, 1);
+
+        // moving the middle level far is one change, pulling both ends in is two
+        let repaired = repair(&[1, 20, 3], &policy, Objective::ChangedLevels).unwrap();
+        assert_eq!(repaired, Repair { levels: vec![1, 2, 3], adjustment: 18, changed: 1 });
+        let repaired = repair(&[5, 5, 5, 5], &policy, Objective::ChangedLevels).unwrap();
+        assert_eq!(repaired.changed, 3);
+
+        let impossible = SafetyPolicy { min_step: 4, max_step: 3, ..SafetyPolicy::default() };
+        assert_eq!(repair(&[1, 2], &impossible, Objective::TotalAdjustment), Err(RepairError::Unsatisfiable));
+    }
+
+    #[test]
+    fn test_wide_values() {
+        let policy = SafetyPolicy::default();
+        let repaired = repair(&[1, 4000000000], &policy, Objective::TotalAdjustment).unwrap();
+        assert_eq!(repaired.adjustment, 4000000000 - 1 - 3);
+        assert!(is_safe(&repaired.levels, &policy));
+
+        let policy = SafetyPolicy { max_step: 1 << 30, ..SafetyPolicy::default() };
+        let report = [5, 3000000000, 7, 4294967295, 100, 2];
+        let repaired = repair(&report, &policy, Objective::ChangedLevels).unwrap();
+        assert!(is_safe(&repaired.levels, &policy));
+        assert_eq!(repaired.changed, 3);
+
+        let report: Vec<u32> = (0..400).map(|idx| idx * 10000000).collect();
+        assert_eq!(repair(&report, &policy, Objective::TotalAdjustment), Err(RepairError::TooManyCandidates));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = Lcg::new(9);
+        let policies = [
+            SafetyPolicy::default(),
+            SafetyPolicy { min_step: 2, max_step: 3, allow_equal: true, consistent_direction: true },
+            SafetyPolicy { min_step: 1, max_step: 2, allow_equal: false, consistent_direction: false },
+        ];
+
+        // every report of the same length with values up to 16
+        let candidates = |len: usize| {
+            (0..17u32.pow(len as u32)).map(move |mut code| {
+                (0..len)
+                    .map(|_| {
+                        let level = code % 17;
+                        code /= 17;
+                        level
+                    })
+                    .collect::<Vec<u32>>()
+            })
+        };
+
+        for _ in 0..30 {
+            let len = 1 + rng.below(4) as usize;
+            let report: Vec<u32> = (0..len).map(|_| rng.below(8) as u32).collect();
+            for policy in &policies {
+                let adjustment = |levels: &[u32]| levels.iter().zip(&report).map(|(a, b)| a.abs_diff(*b) as u64).sum::<u64>();
+                let changed = |levels: &[u32]| levels.iter().zip(&report).filter(|(a, b)| a != b).count();
+                let safe: Vec<Vec<u32>> = candidates(len).filter(|levels| is_safe(levels, policy)).collect();
+
+                let repaired = repair(&report, policy, Objective::TotalAdjustment).unwrap();
+                assert!(is_safe(&repaired.levels, policy));
+                assert_eq!(Some(repaired.adjustment), safe.iter().map(|levels| adjustment(levels)).min(), "{:?} {:?}", report, policy);
+
+                let repaired = repair(&report, policy, Objective::ChangedLevels).unwrap();
+                assert!(is_safe(&repaired.levels, policy));
+                assert_eq!(Some(repaired.changed), safe.iter().map(|levels| changed(levels)).min(), "{:?} {:?}", report, policy);
+
+                // the breakpoints alone find repairs just as cheap as every value in range
+                let (lowest, highest) = contiguous(&report, policy);
+                let every: Vec<u64> = (lowest..=highest).collect();
+                let values = breakpoints(&report, policy, u64::MAX).unwrap();
+                for objective in [Objective::TotalAdjustment, Objective::ChangedLevels] {
+                    let (a, b) = (repair_over(&report, policy, objective, &values), repair_over(&report, policy, objective, &every));
+                    assert_eq!(a.map(|a| (a.adjustment, a.changed)), b.map(|b| (b.adjustment, b.changed)), "{:?} {:?}", report, policy);
+                }
+            }
+        }
+    }
+}
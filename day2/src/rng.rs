@@ -0,0 +1,25 @@
+// Pseudo-random reports for the tests, seeded so a failing report comes back on the next run.
+
+pub struct Lcg(u64);
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg(seed)
+    }
+
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+
+    // 1 to `max_len` levels that move by at most 4 at each step, so every verdict shows up
+    pub fn report(&mut self, max_len: u64) -> Vec<u32> {
+        let mut level = 20;
+        (0..1 + self.below(max_len))
+            .map(|_| {
+                level = (level + self.below(9) as u32).saturating_sub(4);
+                level
+            })
+            .collect()
+    }
+}
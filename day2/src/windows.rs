@@ -0,0 +1,124 @@
+use std::ops::Range;
+
+use crate::SafetyPolicy;
+
+// The maximal stretches of a report that are safe on their own.
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Analysis {
+    // levels in the report
+    pub levels: usize,
+    pub longest: Range<usize>,
+    // every maximal safe slice, ordered by where it starts
+    pub segments: Vec<Range<usize>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub reports: usize,
+    pub fully_safe: usize,
+    // the longest safe window as a share of its report, averaged over all reports
+    pub average_coverage: f64,
+    // the report with the longest safe window and that window
+    pub longest: Option<(usize, Range<usize>)>,
+}
+
+pub fn safe_segments(report: &[u32], policy: &SafetyPolicy) -> Vec<Range<usize>> {
+    let mut segments: Vec<Range<usize>> = Vec::new();
+    for &ascending in policy.directions() {
+        let mut start = 0;
+        for end in 1..=report.len() {
+            let extends = end < report.len() && policy.allows_step(report[end - 1], report[end], ascending);
+            if !extends {
+                segments.push(start..end);
+                start = end;
+            }
+        }
+    }
+
+    // longest first among equal starts, so anything contained in an earlier segment comes
+    // after it
+    segments.sort_by_key(|segment| (segment.start, usize::MAX - segment.end));
+    let mut maximal: Vec<Range<usize>> = Vec::new();
+    for segment in segments {
+        if maximal.last().is_none_or(|last| segment.end > last.end) {
+            maximal.push(segment);
+        }
+    }
+    maximal
+}
+
+pub fn analyze(report: &[u32], policy: &SafetyPolicy) -> Analysis {
+    let segments = safe_segments(report, policy);
+    let longest = segments
+        .iter()
+        .cloned()
+        .reduce(|longest, segment| if segment.len() > longest.len() { segment } else { longest })
+        .unwrap_or(0..0);
+    Analysis { levels: report.len(), longest, segments }
+}
+
+// Totals over reports already analyzed, in input order.
+pub fn summarize(analyses: &[Analysis]) -> Summary {
+    let mut summary = Summary { reports: analyses.len(), fully_safe: 0, average_coverage: 0.0, longest: None };
+    let mut coverage = 0.0;
+
+    for (idx, analysis) in analyses.iter().enumerate() {
+        if analysis.longest.len() == analysis.levels {
+            summary.fully_safe += 1;
+        }
+        if analysis.levels > 0 {
+            coverage += analysis.longest.len() as f64 / analysis.levels as f64;
+        }
+        if summary.longest.as_ref().is_none_or(|(_, longest)| analysis.longest.len() > longest.len()) {
+            summary.longest = Some((idx, analysis.longest.clone()));
+        }
+    }
+    if !analyses.is_empty() {
+        summary.average_coverage = coverage / analyses.len() as f64;
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{is_safe, parse_input};
+
+    #[test]
+    fn test_segments() {
+        let policy = SafetyPolicy::default();
+        assert_eq!(safe_segments(&[1, 2, 7, 8, 9], &policy), vec![0..2, 2..5]);
+        assert_eq!(safe_segments(&[1, 3, 2, 4, 5], &policy), vec![0..2, 1..3, 2..5]);
+        assert_eq!(safe_segments(&[8, 6, 4, 4, 1], &policy), vec![0..3, 3..5]);
+        assert_eq!(safe_segments(&[5, 20, 40], &policy), vec![0..1, 1..2, 2..3]);
+        assert_eq!(analyze(&[1, 3, 2, 4, 5], &policy).longest, 2..5);
+
+        let policy = SafetyPolicy { allow_equal: true, ..SafetyPolicy::default() };
+        assert_eq!(safe_segments(&[1, 2, 2, 1, 1, 4], &policy), vec![0..3, 1..5, 3..6]);
+
+        // every maximal segment is safe and cannot grow on either side
+        let report = [3, 5, 5, 4, 2, 9, 10, 11, 11, 7];
+        for segment in safe_segments(&report, &policy) {
+            assert!(is_safe(&report[segment.clone()], &policy));
+            assert!(segment.start == 0 || !is_safe(&report[segment.start - 1..segment.end], &policy));
+            assert!(segment.end == report.len() || !is_safe(&report[segment.start..segment.end + 1], &policy));
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let reports = parse_input("7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9");
+        let analyses: Vec<Analysis> = reports.iter().map(|report| analyze(report, &SafetyPolicy::default())).collect();
+        let summary = summarize(&analyses);
+        assert_eq!(summary.reports, 6);
+        assert_eq!(summary.fully_safe, 2);
+        assert_eq!(summary.longest, Some((0, 0..5)));
+        assert_eq!(summary.average_coverage, (1.0 + 0.6 + 0.6 + 0.6 + 0.6 + 1.0) / 6.0);
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt::Write;
+
+use crate::operand::OperandRules;
+use crate::scanner::{scan, Instruction, Token};
+
+// Where every recognized instruction sits in the dump and what it adds to the part two total,
+// for checking an answer against the raw memory.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub token: Token,
+    // 1-based, columns count characters rather than bytes
+    pub line: usize,
+    pub column: usize,
+    // whether multiplications were enabled when the instruction was reached
+    pub enabled: bool,
+    pub contribution: i128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    // colors for a terminal
+    Ansi,
+    // brackets for anywhere else: [enabled mul], {disabled mul}, <do or don't>
+    Plain,
+}
+
+const GREEN: &str = "\x1b[1;32m";
+const STRUCK: &str = "\x1b[2;9;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+pub fn disassemble(input: &str, rules: &OperandRules) -> Vec<Entry> {
+    let mut enabled = true;
+    let (mut cursor, mut line, mut column) = (0, 1, 1);
+
+    scan(input, rules)
+        .into_iter()
+        .map(|token| {
+            for c in input[cursor..token.span.start].chars() {
+                if c == '\n' {
+                    line += 1;
+                    column = 1;
+                } else {
+                    column += 1;
+                }
+            }
+            cursor = token.span.start;
+
+            let contribution = match token.instruction {
+                Instruction::Mul(a, b) if enabled => a as i128 * b as i128,
+                _ => 0,
+            };
+            let entry = Entry { token, line, column, enabled, contribution };
+            match entry.token.instruction {
+                Instruction::Do => enabled = true,
+                Instruction::Dont => enabled = false,
+                Instruction::Mul(..) => {}
+            }
+            entry
+        })
+        .collect()
+}
+
+// The whole input with the recognized instructions marked up.
+pub fn highlight(input: &str, entries: &[Entry], style: Style) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut cursor = 0;
+
+    for entry in entries {
+        let span = entry.token.span.clone();
+        out.push_str(&input[cursor..span.start]);
+        let text = &input[span.clone()];
+        let marked = match (style, entry.token.instruction, entry.enabled) {
+            (Style::Ansi, Instruction::Mul(..), true) => format!("{}{}{}", GREEN, text, RESET),
+            (Style::Ansi, Instruction::Mul(..), false) => format!("{}{}{}", STRUCK, text, RESET),
+            (Style::Ansi, _, _) => format!("{}{}{}", YELLOW, text, RESET),
+            (Style::Plain, Instruction::Mul(..), true) => format!("[{}]", text),
+            (Style::Plain, Instruction::Mul(..), false) => format!("{{{}}}", text),
+            (Style::Plain, _, _) => format!("<{}>", text),
+        };
+        out.push_str(&marked);
+        cursor = span.end;
+    }
+    out.push_str(&input[cursor..]);
+    out
+}
+
+// One row per instruction with its offset, position and contribution, then the total.
+pub fn listing(input: &str, entries: &[Entry]) -> String {
+    let mut out = format!("{:>10}  {:>12}  {:<20}  {}\n", "offset", "line:col", "instruction", "contribution");
+    for entry in entries {
+        let contribution = match entry.token.instruction {
+            Instruction::Mul(..) if entry.enabled => entry.contribution.to_string(),
+            Instruction::Mul(..) => "disabled".to_string(),
+            Instruction::Do | Instruction::Dont => String::new(),
+        };
+        let position = format!("{}:{}", entry.line, entry.column);
+        let text = &input[entry.token.span.clone()];
+        let row = format!("{:>10}  {:>12}  {:<20}  {}", entry.token.span.start, position, text, contribution);
+        writeln!(out, "{}", row.trim_end()).unwrap();
+    }
+    match entries.iter().try_fold(0i128, |acc, entry| acc.checked_add(entry.contribution)) {
+        Some(total) => writeln!(out, "total: {}", total).unwrap(),
+        None => writeln!(out, "total: overflows an i128").unwrap(),
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)\n+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_disassemble() {
+        let entries = disassemble(INPUT, &OperandRules::default());
+        let positions: Vec<(usize, usize, bool, i128)> =
+            entries.iter().map(|entry| (entry.line, entry.column, entry.enabled, entry.contribution)).collect();
+        assert_eq!(
+            positions,
+            vec![(1, 2, true, 8), (1, 21, true, 0), (1, 29, false, 0), (2, 13, false, 0), (2, 24, false, 0), (2, 29, true, 40)]
+        );
+        assert_eq!(entries.iter().map(|entry| entry.contribution).sum::<i128>(), 48);
+
+        // columns are in characters, whatever the width of what comes before
+        let entries = disassemble("é\nüümul(1,2)", &OperandRules::default());
+        assert_eq!((entries[0].line, entries[0].column, entries[0].token.span.start), (2, 3, 7));
+    }
+
+    #[test]
+    fn test_views() {
+        let entries = disassemble(INPUT, &OperandRules::default());
+        assert_eq!(
+            highlight(INPUT, &entries, Style::Plain),
+            "x[mul(2,4)]&mul[3,7]!^<don't()>_{mul(5,5)}\n+mul(32,64]({mul(11,8)}un<do()>?[mul(8,5)])"
+        );
+        assert!(highlight(INPUT, &entries, Style::Ansi).contains("\x1b[2;9;31mmul(5,5)\x1b[0m"));
+
+        let listing = listing(INPUT, &entries);
+        let lines: Vec<&str> = listing.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[1].split_whitespace().collect::<Vec<_>>(), vec!["1", "1:2", "mul(2,4)", "8"]);
+        assert_eq!(lines[3].split_whitespace().collect::<Vec<_>>(), vec!["28", "1:29", "mul(5,5)", "disabled"]);
+        assert_eq!(lines[2].trim_start(), "20          1:21  don't()");
+        assert_eq!(lines[7], "total: 48");
+    }
+}
@@ -0,0 +1,84 @@
+pub mod disassembly;
+pub mod operand;
+pub mod scanner;
+pub mod stream;
+pub mod vm;
+
+#[cfg(test)]
+mod rng;
+
+use std::fmt;
+
+use operand::OperandRules;
+use scanner::{Instruction, Scanner};
+
+// A total that no longer fits an i128, at the instruction starting at `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub offset: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "total overflows at the instruction starting at byte {}", self.offset)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+// A single product of two operands of up to 63 bits always fits an i128, their sum may not.
+pub(crate) fn add_product(acc: i128, a: i64, b: i64, offset: usize) -> Result<i128, Overflow> {
+    acc.checked_add(a as i128 * b as i128).ok_or(Overflow { offset })
+}
+
+pub fn part_one(input: &str, rules: &OperandRules) -> Result<i128, Overflow> {
+    Scanner::new(input, rules).try_fold(0, |acc, token| match token.instruction {
+        Instruction::Mul(a, b) => add_product(acc, a, b, token.span.start),
+        Instruction::Do | Instruction::Dont => Ok(acc),
+    })
+}
+
+pub fn part_two(input: &str, rules: &OperandRules) -> Result<i128, Overflow> {
+    let mut enabled = true;
+
+    Scanner::new(input, rules).try_fold(0, |acc, token| match token.instruction {
+        Instruction::Mul(a, b) if enabled => add_product(acc, a, b, token.span.start),
+        Instruction::Mul(..) => Ok(acc),
+        Instruction::Do => {
+            enabled = true;
+            Ok(acc)
+        }
+        Instruction::Dont => {
+            enabled = false;
+            Ok(acc)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_part_one() {
+        assert_eq!(part_one(INPUT, &OperandRules::default()), Ok(161));
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(part_two(INPUT, &OperandRules::default()), Ok(48));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let rules = OperandRules { max_digits: 19, bits: 63, ..OperandRules::default() };
+        let max = "mul(9223372036854775807,9223372036854775807)";
+        assert_eq!(part_one(max, &rules), Ok(i64::MAX as i128 * i64::MAX as i128));
+        // two such products still fit, a third does not
+        let input = format!("{}{}don't(){}", max, max, max);
+        assert_eq!(part_one(&input, &rules), Err(Overflow { offset: 95 }));
+        assert!(part_two(&input, &rules).is_ok());
+    }
+}
\ No newline at end of file
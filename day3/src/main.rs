@@ -1,55 +1,118 @@
-use regex::Regex;
+use std::fs::File;
+use std::io::IsTerminal;
+use std::process;
 
-fn part_one(input: &str) -> u32 {
-    // match 2 groups: ""a", "b")
-    let re = Regex::new(r"mul\(([1-9]|[1-9][0-9]|[1-9][0-9][0-9]),([1-9]|[1-9][0-9]|[1-9][0-9][0-9])\)").unwrap();
+use day3::disassembly::{disassemble, highlight, listing, Style};
+use day3::operand::OperandRules;
+use day3::stream;
+use day3::vm::{InstructionSet, Machine};
 
-    re.captures_iter(input).map(|c| c.extract()).fold(0, |acc, (_, [a, b])| {
-        acc + (a.parse::<u32>().unwrap() * b.parse::<u32>().unwrap())
-    })
+const USAGE: &str = "usage:
+    day3 [RULES]
+    day3 run [--instructions NAME,..] [RULES]
+    day3 disassemble [--color auto|always|never] [RULES]
+
+RULES are [--rules PATH] [--min-digits N] [--max-digits N] [--allow-zero true|false]
+          [--allow-leading-zeros true|false] [--allow-sign true|false] [--bits N]
+
+run executes the instructions on a machine with an accumulator and prints what is left in
+it, the instructions are picked from mul, do, don't, add, sub and reset (mul,do,don't by
+default)
+
+the rules file holds `key = value` lines for min_digits, max_digits, allow_zero,
+allow_leading_zeros, allow_sign and bits, the other flags override it";
+
+// the dump is streamed through in chunks of this size rather than read in whole
+const CHUNK_SIZE: usize = 1 << 20;
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
 }
 
-fn part_two(input: &str) -> u32 {
-    // match 4 groups: "don't()", "do()", "a", "b")
-    let re = Regex::new(r"(don't\(\))|(do\(\))|mul\(([1-9]|[1-9][0-9]|[1-9][0-9][0-9]),([1-9]|[1-9][0-9]|[1-9][0-9][0-9])\)").unwrap();
-    let mut process = true;
-
-    re.captures_iter(input).fold(0, |acc, c| {
-        let mut sum = 0;
-
-        if c.get(1).is_some_and(|x| x.as_str() == "don't()") {
-            process = false;
-        };
-        if c.get(2).is_some_and(|x| x.as_str() == "do()") {
-            process = true;
-        };
-        if process && c.get(3).is_some() && c.get(4).is_some() {
-            sum = c.get(3).unwrap().as_str().parse::<u32>().unwrap() * c.get(4).unwrap().as_str().parse::<u32>().unwrap();
+fn parse_rules(args: &[String]) -> OperandRules {
+    if !args.len().is_multiple_of(2) {
+        fail(&format!("missing value for {}", args[args.len() - 1]));
+    }
+    // the rules file is read first, so the other flags override it whatever their order
+    let mut rules = match args.chunks(2).rfind(|pair| pair[0] == "--rules") {
+        Some(pair) => {
+            let config = std::fs::read_to_string(&pair[1]).unwrap_or_else(|err| fail(&format!("{}: {}", pair[1], err)));
+            OperandRules::from_config(&config).unwrap_or_else(|message| fail(&format!("{}: {}", pair[1], message)))
+        }
+        None => OperandRules::default(),
+    };
+    for pair in args.chunks(2) {
+        match pair[0].as_str() {
+            "--rules" => {}
+            "--min-digits" | "--max-digits" | "--allow-zero" | "--allow-leading-zeros" | "--allow-sign" | "--bits" => {
+                rules.set(&pair[0][2..].replace('-', "_"), &pair[1]).unwrap_or_else(|message| fail(&message))
+            }
+            flag => fail(&format!("unknown flag: {}", flag)),
         }
-        acc + sum
-    })
+    }
+    rules.validate().unwrap_or_else(|message| fail(&message));
+    rules
 }
 
-fn main() {
+fn run(args: &[String]) {
+    let (set, args) = match args {
+        [flag, names, rest @ ..] if flag == "--instructions" => {
+            let names: Vec<&str> = names.split(',').map(str::trim).collect();
+            (InstructionSet::extended().select(&names).unwrap_or_else(|message| fail(&message)), rest)
+        }
+        [flag] if flag == "--instructions" => fail("missing value for --instructions"),
+        _ => (InstructionSet::standard(), args),
+    };
+    let rules = parse_rules(args);
+
     let input = std::fs::read_to_string("./inputs/day3.txt").unwrap();
-    let part_one = part_one(&input);
-    let part_two = part_two(&input);
-    println!("part 1: {} part 2: {}", part_one, part_two);
+    let calls = set.scan(&input, &rules);
+    let accumulator = Machine::new().run(&set, &calls).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    println!("{} instructions, accumulator: {}", calls.len(), accumulator);
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+fn print_disassembly(args: &[String]) {
+    let (color, args) = match args {
+        [flag, color, rest @ ..] if flag == "--color" => (color.as_str(), rest),
+        [flag] if flag == "--color" => fail("missing value for --color"),
+        _ => ("auto", args),
+    };
+    let style = match color {
+        "auto" if std::io::stdout().is_terminal() => Style::Ansi,
+        "auto" | "never" => Style::Plain,
+        "always" => Style::Ansi,
+        _ => fail(&format!("unknown color mode: {}", color)),
+    };
+    let rules = parse_rules(args);
 
-    #[test]
-    fn test_part_one() {
-        assert_eq!(part_one(INPUT), 161);
+    let input = std::fs::read_to_string("./inputs/day3.txt").unwrap();
+    let entries = disassemble(&input, &rules);
+    let text = highlight(&input, &entries, style);
+    print!("{}", text);
+    if !text.ends_with('\n') {
+        println!();
     }
+    println!();
+    print!("{}", listing(&input, &entries));
+}
 
-    #[test]
-    fn test_part_two() {
-        assert_eq!(part_two(INPUT), 48);
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("run") {
+        return run(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("disassemble") {
+        return print_disassembly(&args[1..]);
     }
-}
\ No newline at end of file
+    let rules = parse_rules(&args);
+
+    let (part_one, part_two) = stream::solve(File::open("./inputs/day3.txt").unwrap(), CHUNK_SIZE, &rules).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    println!("part 1: {} part 2: {}", part_one, part_two);
+}
@@ -0,0 +1,141 @@
+use aoc_config::parse_key_values;
+
+// What counts as a `mul` operand. The default is the puzzle's rule: 1 to 3 digits, no sign,
+// no leading zeros and never 0 itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperandRules {
+    pub min_digits: usize,
+    pub max_digits: usize,
+    pub allow_zero: bool,
+    // `007`, and `00` when zero is allowed too
+    pub allow_leading_zeros: bool,
+    // a `+` or `-` right before the digits
+    pub allow_sign: bool,
+    // operands must fit a signed integer of this many bits when signs are allowed, an
+    // unsigned one otherwise
+    pub bits: u32,
+}
+
+impl Default for OperandRules {
+    fn default() -> Self {
+        OperandRules {
+            min_digits: 1,
+            max_digits: 3,
+            allow_zero: false,
+            allow_leading_zeros: false,
+            allow_sign: false,
+            bits: 32,
+        }
+    }
+}
+
+impl OperandRules {
+    // Reads a `key = value` file, rules that are not given keep their default.
+    pub fn from_config(config: &str) -> Result<OperandRules, String> {
+        let mut rules = OperandRules::default();
+        parse_key_values(config, |key, value| rules.set(key, value))?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let invalid = || format!("invalid value for {}: {}", key, value);
+        match key {
+            "min_digits" => self.min_digits = value.parse().map_err(|_| invalid())?,
+            "max_digits" => self.max_digits = value.parse().map_err(|_| invalid())?,
+            "allow_zero" => self.allow_zero = value.parse().map_err(|_| invalid())?,
+            "allow_leading_zeros" => self.allow_leading_zeros = value.parse().map_err(|_| invalid())?,
+            "allow_sign" => self.allow_sign = value.parse().map_err(|_| invalid())?,
+            "bits" => self.bits = value.parse().map_err(|_| invalid())?,
+            _ => return Err(format!("unknown rule: {}", key)),
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_digits == 0 || self.min_digits > self.max_digits {
+            return Err(format!("digits must be at least 1 and min_digits {} at most max_digits {}", self.min_digits, self.max_digits));
+        }
+        if !(1..=63).contains(&self.bits) {
+            return Err(format!("bits must be between 1 and 63, got {}", self.bits));
+        }
+        Ok(())
+    }
+
+    // worked out in i128 since 1 << 63 does not fit an i64
+    fn range(&self) -> (i64, i64) {
+        let (min, max) = match self.allow_sign {
+            true => (-(1i128 << (self.bits - 1)), (1i128 << (self.bits - 1)) - 1),
+            false => (0, (1i128 << self.bits) - 1),
+        };
+        (min as i64, max as i64)
+    }
+
+    // The operand at the start of `input` and how many bytes it takes up. The digits are
+    // always read up to the first non-digit, so `1234` is too long rather than `123` followed
+    // by a stray `4`.
+    pub fn read(&self, input: &[u8]) -> Option<(i64, usize)> {
+        let sign = match input.first() {
+            Some(&byte @ (b'+' | b'-')) if self.allow_sign => Some(byte),
+            _ => None,
+        };
+        let start = sign.map_or(0, |_| 1);
+        let digits = &input[start..];
+        let len = digits.iter().take_while(|byte| byte.is_ascii_digit()).count();
+        if !(self.min_digits..=self.max_digits).contains(&len) {
+            return None;
+        }
+        if len > 1 && digits[0] == b'0' && !self.allow_leading_zeros {
+            return None;
+        }
+
+        let magnitude = digits[..len]
+            .iter()
+            .try_fold(0i64, |acc, byte| acc.checked_mul(10)?.checked_add((byte - b'0') as i64))?;
+        let value = if sign == Some(b'-') { -magnitude } else { magnitude };
+        let (min, max) = self.range();
+        if (value == 0 && !self.allow_zero) || value < min || value > max {
+            return None;
+        }
+        Some((value, start + len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let rules = OperandRules::default();
+        assert_eq!(rules.read(b"123,"), Some((123, 3)));
+        assert_eq!(rules.read(b"1234,"), None);
+        assert_eq!(rules.read(b"012"), None);
+        assert_eq!(rules.read(b"0)"), None);
+        assert_eq!(rules.read(b"-5"), None);
+
+        let rules = OperandRules { allow_zero: true, allow_leading_zeros: true, allow_sign: true, bits: 8, ..rules };
+        assert_eq!(rules.read(b"0)"), Some((0, 1)));
+        assert_eq!(rules.read(b"007"), Some((7, 3)));
+        assert_eq!(rules.read(b"-128"), Some((-128, 4)));
+        assert_eq!(rules.read(b"+127"), Some((127, 4)));
+        assert_eq!(rules.read(b"128"), None);
+        assert_eq!(rules.read(b"-"), None);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let rules = OperandRules::from_config("max_digits = 5 # wider dumps\nallow_zero = true").unwrap();
+        assert_eq!(rules, OperandRules { max_digits: 5, allow_zero: true, ..OperandRules::default() });
+        assert_eq!(rules.read(b"65535"), Some((65535, 5)));
+
+        assert!(OperandRules::from_config("bits = 64").is_err());
+        let rules = OperandRules::from_config("max_digits = 19\nbits = 63").unwrap();
+        assert_eq!(rules.read(b"9223372036854775807"), Some((i64::MAX, 19)));
+        assert_eq!(rules.read(b"9223372036854775808"), None);
+        let rules = OperandRules { allow_sign: true, ..rules };
+        assert_eq!(rules.read(b"-4611686018427387904"), Some((-(1 << 62), 20)));
+        assert_eq!(rules.read(b"4611686018427387904"), None);
+        assert_eq!(OperandRules::from_config("sign = true"), Err("line 1: unknown rule: sign".to_string()));
+    }
+}
@@ -0,0 +1,140 @@
+use std::ops::Range;
+
+use crate::operand::OperandRules;
+
+// Turns corrupted memory into the instructions hidden in it. Everything that is not a
+// well formed instruction is skipped, one byte at a time, so an instruction can start
+// inside the wreckage of a broken one (`mul(1,mul(2,3)` still holds `mul(2,3)`).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Instruction {
+    Mul(i64, i64),
+    Do,
+    Dont,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Token {
+    pub instruction: Instruction,
+    // byte offsets of the instruction in the input
+    pub span: Range<usize>,
+}
+
+pub struct Scanner<'a> {
+    input: &'a [u8],
+    position: usize,
+    rules: OperandRules,
+}
+
+// Matches `name(arg, ..)` with exactly `arity` operands right at `start`, returning the
+// operands and where the call ends.
+pub(crate) fn call_at(input: &[u8], start: usize, name: &str, arity: usize, rules: &OperandRules) -> Option<(Vec<i64>, usize)> {
+    if !input[start..].starts_with(name.as_bytes()) {
+        return None;
+    }
+    let mut at = start + name.len();
+    let expect = |at: &mut usize, byte: u8| {
+        if input.get(*at) != Some(&byte) {
+            return None;
+        }
+        *at += 1;
+        Some(())
+    };
+
+    expect(&mut at, b'(')?;
+    let mut args = Vec::with_capacity(arity);
+    for idx in 0..arity {
+        if idx > 0 {
+            expect(&mut at, b',')?;
+        }
+        let (value, len) = rules.read(&input[at..])?;
+        args.push(value);
+        at += len;
+    }
+    expect(&mut at, b')')?;
+    Some((args, at))
+}
+
+// The instruction starting right at `start` and where it ends.
+pub(crate) fn instruction_at(input: &[u8], start: usize, rules: &OperandRules) -> Option<(Instruction, usize)> {
+    if let Some((_, end)) = call_at(input, start, "don't", 0, rules) {
+        return Some((Instruction::Dont, end));
+    }
+    if let Some((_, end)) = call_at(input, start, "do", 0, rules) {
+        return Some((Instruction::Do, end));
+    }
+    let (args, end) = call_at(input, start, "mul", 2, rules)?;
+    Some((Instruction::Mul(args[0], args[1]), end))
+}
+
+// The most bytes an instruction can take up, `mul(` with two signed operands of max_digits.
+pub(crate) fn longest_instruction(rules: &OperandRules) -> usize {
+    "don't()".len().max("mul(,)".len() + 2 * (rules.max_digits + 1))
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a str, rules: &OperandRules) -> Self {
+        Scanner { input: input.as_bytes(), position: 0, rules: rules.clone() }
+    }
+}
+
+impl Iterator for Scanner<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        while self.position < self.input.len() {
+            let start = self.position;
+            match instruction_at(self.input, start, &self.rules) {
+                Some((instruction, end)) => {
+                    self.position = end;
+                    return Some(Token { instruction, span: start..end });
+                }
+                None => self.position += 1,
+            }
+        }
+        None
+    }
+}
+
+pub fn scan(input: &str, rules: &OperandRules) -> Vec<Token> {
+    Scanner::new(input, rules).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        let tokens = scan("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))", &OperandRules::default());
+        let instructions: Vec<Instruction> = tokens.iter().map(|token| token.instruction).collect();
+        assert_eq!(
+            instructions,
+            vec![
+                Instruction::Mul(2, 4),
+                Instruction::Dont,
+                Instruction::Mul(5, 5),
+                Instruction::Mul(11, 8),
+                Instruction::Do,
+                Instruction::Mul(8, 5),
+            ]
+        );
+        assert_eq!(tokens[0].span, 1..9);
+        assert_eq!(tokens[1].span, 20..27);
+        assert_eq!(tokens[4].span, 59..63);
+    }
+
+    #[test]
+    fn test_malformed() {
+        let instructions = |input| scan(input, &OperandRules::default()).into_iter().map(|token| token.instruction).collect::<Vec<_>>();
+        assert_eq!(instructions("mul(1234,5) mul(012,5) mul(0,5) mul( 1,2) mul(1,2"), vec![]);
+        assert_eq!(instructions("mul(1,mul(2,3)"), vec![Instruction::Mul(2, 3)]);
+        assert_eq!(instructions("mul(999,1)don't(do()"), vec![Instruction::Mul(999, 1), Instruction::Do]);
+
+        let rules = OperandRules { allow_zero: true, allow_sign: true, ..OperandRules::default() };
+        let instructions: Vec<_> = scan("mul(0,5)mul(-3,+4)mul(--3,4)", &rules).into_iter().map(|token| token.instruction).collect();
+        assert_eq!(instructions, vec![Instruction::Mul(0, 5), Instruction::Mul(-3, 4)]);
+    }
+}
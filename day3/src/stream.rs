@@ -0,0 +1,147 @@
+use std::io::{self, Read};
+
+use crate::add_product;
+use crate::operand::OperandRules;
+use crate::scanner::{instruction_at, longest_instruction, Instruction, Token};
+
+// The scanner over a reader, for dumps too big to hold in memory. Instructions cut by a
+// chunk boundary are still seen whole, spans are offsets into the whole stream.
+
+pub struct StreamScanner<R> {
+    reader: R,
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    // scan position in `buffer`
+    position: usize,
+    // stream offset of `buffer[0]`
+    offset: usize,
+    lookahead: usize,
+    done: bool,
+    rules: OperandRules,
+}
+
+impl<R: Read> StreamScanner<R> {
+    pub fn new(reader: R, chunk_size: usize, rules: &OperandRules) -> Self {
+        let chunk_size = chunk_size.max(1);
+        StreamScanner {
+            reader,
+            chunk_size,
+            buffer: Vec::with_capacity(chunk_size + longest_instruction(rules)),
+            position: 0,
+            offset: 0,
+            lookahead: longest_instruction(rules),
+            done: false,
+            rules: rules.clone(),
+        }
+    }
+
+    fn refill(&mut self) -> io::Result<()> {
+        self.buffer.drain(..self.position);
+        self.offset += self.position;
+        self.position = 0;
+
+        let len = self.buffer.len();
+        self.buffer.resize(len + self.chunk_size, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buffer[len..]) {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                result => break result,
+            }
+        };
+        match read {
+            Ok(read) => {
+                self.buffer.truncate(len + read);
+                self.done = read == 0;
+                Ok(())
+            }
+            Err(err) => {
+                self.buffer.truncate(len);
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamScanner<R> {
+    type Item = io::Result<Token>;
+
+    fn next(&mut self) -> Option<io::Result<Token>> {
+        loop {
+            if !self.done && self.buffer.len() - self.position < self.lookahead {
+                if let Err(err) = self.refill() {
+                    return Some(Err(err));
+                }
+                continue;
+            }
+            if self.position >= self.buffer.len() {
+                return None;
+            }
+            let start = self.position;
+            match instruction_at(&self.buffer, start, &self.rules) {
+                Some((instruction, end)) => {
+                    self.position = end;
+                    return Some(Ok(Token { instruction, span: self.offset + start..self.offset + end }));
+                }
+                None => self.position += 1,
+            }
+        }
+    }
+}
+
+// Both parts in one pass over the reader, the enabled flag simply carries on from chunk to
+// chunk. An overflowing total is reported as invalid data.
+pub fn solve(reader: impl Read, chunk_size: usize, rules: &OperandRules) -> io::Result<(i128, i128)> {
+    let (mut part_one, mut part_two) = (0, 0);
+    let mut enabled = true;
+    let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, err);
+
+    for token in StreamScanner::new(reader, chunk_size, rules) {
+        let token = token?;
+        match token.instruction {
+            Instruction::Mul(a, b) => {
+                part_one = add_product(part_one, a, b, token.span.start).map_err(invalid)?;
+                if enabled {
+                    part_two = add_product(part_two, a, b, token.span.start).map_err(invalid)?;
+                }
+            }
+            Instruction::Do => enabled = true,
+            Instruction::Dont => enabled = false,
+        }
+    }
+    Ok((part_one, part_two))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Lcg;
+    use crate::scanner::scan;
+    use crate::{part_one, part_two};
+
+    #[test]
+    fn test_chunk_boundaries() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let rules = OperandRules::default();
+        for chunk_size in 1..=input.len() + 1 {
+            let tokens: Vec<Token> = StreamScanner::new(input.as_bytes(), chunk_size, &rules).map(Result::unwrap).collect();
+            assert_eq!(tokens, scan(input, &rules));
+            assert_eq!(solve(input.as_bytes(), chunk_size, &rules).unwrap(), (161, 48));
+        }
+    }
+
+    #[test]
+    fn test_matches_scan() {
+        let mut rng = Lcg::new(3);
+        let pieces = ["mul(", "do()", "don't()", ",", ")", "-", "0", "7", "12", "345", "x", "\n"];
+        let input: String = (0..5000).map(|_| pieces[rng.below(pieces.len() as u64) as usize]).collect();
+
+        for rules in [OperandRules::default(), OperandRules { max_digits: 5, allow_zero: true, allow_sign: true, ..OperandRules::default() }] {
+            for chunk_size in [1, 2, 3, 7, 13, 64, 4096] {
+                let tokens: Vec<Token> = StreamScanner::new(input.as_bytes(), chunk_size, &rules).map(Result::unwrap).collect();
+                assert_eq!(tokens, scan(&input, &rules));
+                let expected = (part_one(&input, &rules).unwrap(), part_two(&input, &rules).unwrap());
+                assert_eq!(solve(input.as_bytes(), chunk_size, &rules).unwrap(), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::ops::Range;
+
+use crate::operand::OperandRules;
+use crate::scanner::call_at;
+use crate::Overflow;
+
+// A registry of `name(operands)` instructions and a small machine to run them.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    pub accumulator: i128,
+    pub enabled: bool,
+    // where the call being executed starts, for its overflow error
+    offset: usize,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Machine { accumulator: 0, enabled: true, offset: 0 }
+    }
+}
+
+pub type Semantics = fn(&mut Machine, &[i64]) -> Result<(), Overflow>;
+
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub name: String,
+    pub arity: usize,
+    pub semantics: Semantics,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    // index of the definition in its instruction set
+    pub opcode: usize,
+    pub args: Vec<i64>,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InstructionSet {
+    definitions: Vec<Definition>,
+}
+
+impl InstructionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // mul, do and don't, running them gives the part two answer
+    pub fn standard() -> Self {
+        let mut set = InstructionSet::new();
+        set.define("mul", 2, |machine, args| match machine.enabled {
+            true => machine.add((args[0] as i128).checked_mul(args[1] as i128)),
+            false => Ok(()),
+        })
+        .define("do", 0, |machine, _| {
+            machine.enabled = true;
+            Ok(())
+        })
+        .define("don't", 0, |machine, _| {
+            machine.enabled = false;
+            Ok(())
+        });
+        set
+    }
+
+    // the standard set plus add, sub and reset
+    pub fn extended() -> Self {
+        let mut set = InstructionSet::standard();
+        set.define("add", 2, |machine, args| match machine.enabled {
+            true => machine.add((args[0] as i128).checked_add(args[1] as i128)),
+            false => Ok(()),
+        })
+        .define("sub", 2, |machine, args| match machine.enabled {
+            true => machine.add((args[0] as i128).checked_sub(args[1] as i128)),
+            false => Ok(()),
+        })
+        .define("reset", 0, |machine, _| {
+            if machine.enabled {
+                machine.accumulator = 0;
+            }
+            Ok(())
+        });
+        set
+    }
+
+    // Adds an instruction, or replaces the one that already has this name.
+    pub fn define(&mut self, name: &str, arity: usize, semantics: Semantics) -> &mut Self {
+        let definition = Definition { name: name.to_string(), arity, semantics };
+        match self.definitions.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = definition,
+            None => self.definitions.push(definition),
+        }
+        self
+    }
+
+    // Keeps only the named instructions, in the order given.
+    pub fn select(&self, names: &[&str]) -> Result<InstructionSet, String> {
+        let definitions = names
+            .iter()
+            .map(|name| self.get(name).cloned().ok_or_else(|| format!("unknown instruction: {}", name)))
+            .collect::<Result<_, _>>()?;
+        Ok(InstructionSet { definitions })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Definition> {
+        self.definitions.iter().find(|definition| definition.name == name)
+    }
+
+    pub fn definition(&self, opcode: usize) -> &Definition {
+        &self.definitions[opcode]
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.definitions.iter().map(|definition| definition.name.as_str()).collect()
+    }
+
+    // The name must be followed by `(` and the call is only accepted with exactly the right
+    // number of operands, so no name can shadow another.
+    pub fn scan(&self, input: &str, rules: &OperandRules) -> Vec<Call> {
+        let input = input.as_bytes();
+        let mut calls = Vec::new();
+        let mut position = 0;
+
+        while position < input.len() {
+            let call = self.definitions.iter().enumerate().find_map(|(opcode, definition)| {
+                call_at(input, position, &definition.name, definition.arity, rules).map(|(args, end)| (opcode, args, end))
+            });
+            match call {
+                Some((opcode, args, end)) => {
+                    calls.push(Call { opcode, args, span: position..end });
+                    position = end;
+                }
+                None => position += 1,
+            }
+        }
+        calls
+    }
+}
+
+impl Machine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds to the accumulator, `None` being a value that already overflowed.
+    pub fn add(&mut self, value: Option<i128>) -> Result<(), Overflow> {
+        self.accumulator = value.and_then(|value| self.accumulator.checked_add(value)).ok_or(Overflow { offset: self.offset })?;
+        Ok(())
+    }
+
+    // `offset` is where the call starts, it ends up in the error if the call overflows
+    pub fn execute(&mut self, definition: &Definition, args: &[i64], offset: usize) -> Result<(), Overflow> {
+        self.offset = offset;
+        (definition.semantics)(self, args)
+    }
+
+    pub fn run(&mut self, set: &InstructionSet, calls: &[Call]) -> Result<i128, Overflow> {
+        for call in calls {
+            self.execute(set.definition(call.opcode), &call.args, call.span.start)?;
+        }
+        Ok(self.accumulator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part_one, part_two};
+
+    const INPUT: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_standard_matches_part_two() {
+        let rules = OperandRules::default();
+        let set = InstructionSet::standard();
+        let calls = set.scan(INPUT, &rules);
+
+        assert_eq!(calls[1], Call { opcode: 2, args: vec![], span: 20..27 });
+        assert_eq!(Machine::new().run(&set, &calls), part_two(INPUT, &rules));
+    }
+
+    #[test]
+    fn test_extended() {
+        let rules = OperandRules::default();
+        let set = InstructionSet::extended();
+        let input = "add(1,2)mul(3,4)sub(5,7)]reset(don't()add(9,9)reset()do()sub(1,2)add(1)";
+        let calls = set.scan(input, &rules);
+
+        let names: Vec<&str> = calls.iter().map(|call| set.definition(call.opcode).name.as_str()).collect();
+        assert_eq!(names, vec!["add", "mul", "sub", "don't", "add", "reset", "do", "sub"]);
+        // 3 + 12 - 2, the reset and add(9,9) are disabled, then - 1
+        assert_eq!(Machine::new().run(&set, &calls), Ok(12));
+
+        // a redefinition replaces the original and a selection drops the rest
+        let mut set = set.select(&["mul", "add"]).unwrap();
+        set.define("mul", 2, |machine, args| machine.add(Some(-(args[0] as i128 * args[1] as i128))));
+        assert_eq!(set.names(), vec!["mul", "add"]);
+        assert_eq!(Machine::new().run(&set, &set.scan(input, &rules)), Ok(3 - 12 + 18));
+        assert_eq!(set.select(&["div"]).unwrap_err(), "unknown instruction: div");
+    }
+
+    #[test]
+    fn test_overflow() {
+        let rules = OperandRules { max_digits: 19, bits: 63, ..OperandRules::default() };
+        let max = "mul(9223372036854775807,9223372036854775807)";
+        let product = i64::MAX as i128 * i64::MAX as i128;
+
+        // the same error as part_one, at the third mul
+        let input = max.repeat(3);
+        let set = InstructionSet::standard();
+        assert_eq!(Machine::new().run(&set, &set.scan(&input, &rules)), Err(Overflow { offset: 88 }));
+        assert_eq!(part_one(&input, &rules), Err(Overflow { offset: 88 }));
+
+        // the calls before the overflowing one have run, the ones after it have not
+        let set = InstructionSet::extended();
+        let mut machine = Machine::new();
+        assert_eq!(machine.run(&set, &set.scan(&(max.repeat(3) + "sub(1,2)"), &rules)), Err(Overflow { offset: 88 }));
+        assert_eq!(machine.accumulator, 2 * product);
+
+        let input = max.repeat(2) + "reset()" + max + "sub(1,2)";
+        assert_eq!(Machine::new().run(&set, &set.scan(&input, &rules)), Ok(product - 1));
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Guard {
+    pub location: (usize, usize),
+    pub direction: Direction,
+    pub visited: HashSet<(usize, usize)>,
+    pub visited_with_dir: Vec<(Direction, usize, usize)>,
+    pub num_loops: u32,
+}
+
+impl Guard {
+    pub fn from(location: (usize, usize), direction: Direction) -> Self {
+        let mut visited = HashSet::new();
+        visited.insert(location);
+
+        let visited_with_dir = vec![(direction, location.0, location.1)];
+
+        Guard { location, direction, visited, visited_with_dir, num_loops: 0}
+    }
+
+    fn hit(location: &(usize, usize), obstacles: &Obstacles) -> bool {
+        obstacles.locations.contains(location)
+    }
+
+    fn move_guard(&mut self, obstacles: &Obstacles) {
+        match self.direction {
+            Direction::Up => {
+                let new_location = (self.location.0 - 1, self.location.1);
+                if Self::hit(&new_location, obstacles) {
+                    self.turn();
+                } else {
+                    self.location = new_location;
+                }
+            },
+            Direction::Down => {
+                let new_location = (self.location.0 + 1, self.location.1);
+                if Self::hit(&new_location, obstacles) {
+                    self.turn();
+                } else {
+                    self.location = new_location;
+                }
+            }
+            Direction::Left => {
+                let new_location = (self.location.0, self.location.1 - 1);
+                if Self::hit(&new_location, obstacles) {
+                    self.turn();
+                } else {
+                    self.location = new_location;
+                }
+            },
+            Direction::Right => {
+                let new_location = (self.location.0, self.location.1 + 1);
+                if Self::hit(&new_location, obstacles) {
+                    self.turn();
+                } else {
+                    self.location = new_location;
+                }
+            },
+        }
+        self.visited.insert(self.location);
+        self.visited_with_dir.push((self.direction, self.location.0, self.location.1));
+    }
+
+    fn turn(&mut self) {
+        self.direction = match self.direction {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    fn in_loop(&self) -> bool {
+        let set: HashSet<_> = self.visited_with_dir.iter().collect();
+        if self.visited_with_dir.len() != set.len() {
+            return true
+        }
+        false
+    }
+
+    fn add_loop(&mut self) {
+        self.num_loops += 1;
+    }
+
+    pub fn reset(&mut self, location: (usize, usize), direction: Direction) {
+        self.location = location;
+        
+        let mut visited = HashSet::new();
+        visited.insert(location);
+        self.visited = visited;
+
+        self.visited_with_dir = vec![(direction, location.0, location.1)];
+
+        self.direction = direction;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obstacles {
+    pub locations: Vec<(usize, usize)>,
+}
+
+impl Obstacles {
+    pub fn new() -> Self {
+        Obstacles {
+            locations: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, location: (usize, usize)) {
+        self.locations.push(location);
+    }
+
+    pub fn remove_last(&mut self) {
+        self.locations.pop();
+    }
+}
+
+pub fn parse_input(input: &str) -> (Guard, Obstacles, (usize, usize)) {
+    let mut obstacles = Obstacles::new();
+    let mut guard: Guard = Guard::from((0, 0), Direction::Up);
+
+    let map = input
+        .lines()
+        .enumerate()
+        .map(|(i, x)| x.chars().enumerate().map(move |(j, y)| (i, j, y)).collect::<Vec<_>>()).collect::<Vec<_>>();
+
+    for line in map.iter() {
+        for (i, j, c) in line {
+            match c {
+                '^' => guard = Guard::from((*i, *j), Direction::Up),
+                '#' => obstacles.add((*i, *j)),
+                _ => (),
+            }
+        }
+    }
+    (guard, obstacles, (map.len(), map[0].len()))
+}
+
+pub fn process(guard: &mut Guard, obstacles: &Obstacles, bounds: &(usize, usize)) -> usize {
+    while guard.location.0 < bounds.0 - 1 && guard.location.0 > 0 && guard.location.1 < bounds.1 - 1 && guard.location.1 > 0 {
+        if guard.in_loop() {
+            guard.add_loop();
+            return 0
+        }
+        guard.move_guard(obstacles);
+    }
+    guard.visited.len()
+}
+
+// this is very slow a brute force solution, but it works...
+// TODO: make this better
+pub fn part_two(guard: &mut Guard, obstacles: &mut Obstacles, bounds: &(usize, usize)) -> u32 {
+    let init_location = guard.location;
+    let init_direction = guard.direction;
+
+    process(guard, obstacles, bounds);
+
+    let visited: Vec<(usize, usize)> = guard.visited.iter().copied().collect();
+
+    visited.iter().for_each(|new_obstacle| {
+        if new_obstacle != &init_location {
+            guard.reset(init_location, init_direction);
+            obstacles.add(*new_obstacle);
+            process(guard, obstacles, bounds);
+            obstacles.remove_last();
+        }
+    });
+    guard.num_loops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    #[test]
+    fn test_part_one() {
+        let (mut guard, obstacles, bounds) = parse_input(INPUT);
+        let part_one = process(&mut guard, &obstacles, &bounds);
+        assert_eq!(part_one, 41);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let (mut guard, mut obstacles, bounds) = parse_input(INPUT);
+        let part_two = part_two(&mut guard, &mut obstacles, &bounds);
+        assert_eq!(part_two, 6)
+    }
+}
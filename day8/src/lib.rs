@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+
+pub type Antennas = HashMap<char, Vec<(usize, usize)>>;
+
+pub fn parse_input(input: &str) -> (Antennas, (usize, usize)) {
+    let mut result: Antennas = HashMap::new();
+    let mut x = Vec::new();
+    let mut y = Vec::new();
+
+    for (i, val) in input.lines().rev().enumerate() {
+        y.push(i);
+        for (j, char) in val.chars().enumerate() {
+            x.push(j);
+            if char.is_alphanumeric() {
+                result.entry(char).or_default().push((j, i));
+            }
+        }
+    }
+    let max = (x.into_iter().max().unwrap(), y.into_iter().max().unwrap());
+    (result, max)
+}
+
+fn add_antinodes(coordinates: &[(usize, usize)], max_size: (usize, usize)) -> Vec<(usize, usize)> {
+    coordinates.iter().combinations(2).flat_map(|combination| {
+        let mut antinodes = Vec::new();
+        
+        let antinode_1 = get_next_antinode(*combination[0], *combination[1], max_size, false);
+        if let Some(antinode) = antinode_1 {
+            antinodes.push(antinode);
+        }
+
+        let antinode_2 = get_next_antinode(*combination[0], *combination[1], max_size, true);
+        if let Some(antinode) = antinode_2 {
+            antinodes.push(antinode);
+        }
+
+        antinodes
+    })
+    .collect()
+}
+
+fn get_next_antinode(a: (usize, usize), b: (usize, usize), max_size: (usize, usize), reverse: bool) -> Option<(usize, usize)> {
+    match reverse {
+        false => {
+            let x = a.0 as isize * 2 - b.0  as isize;
+            let y = a.1  as isize * 2 - b.1 as isize;
+            if x >= 0 && y >= 0 && x <= max_size.0 as isize && y <= max_size.1 as isize { Some((x as usize, y as usize)) } else { None }
+        },
+        true => {
+            let x = b.0 as isize * 2 - a.0 as isize;
+            let y = b.1 as isize * 2 - a.1 as isize;
+            if x >= 0 && y >= 0 && x <= max_size.0 as isize && y <= max_size.1 as isize { Some((x as usize, y as usize)) } else { None }
+        },
+    }
+}
+
+fn add_recurring_antinodes(coordinates: &[(usize, usize)], max_size: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut antinodes = Vec::new();
+
+    for combination in coordinates.iter().combinations(2) {
+        let mut combination_a = *combination[0];
+        let mut combination_b = *combination[1];
+        
+        while let Some(antinode) = get_next_antinode(combination_a, combination_b, max_size, false) {
+            antinodes.push(antinode);
+            combination_b = combination_a;
+            combination_a = antinode;
+        }
+
+        combination_a = *combination[0];
+        combination_b = *combination[1];
+
+        while let Some(antinode) = get_next_antinode(combination_a, combination_b, max_size, true) {
+            antinodes.push(antinode);
+            combination_a = combination_b;
+            combination_b = antinode;
+        }
+    }
+    antinodes
+}
+
+pub fn part_one(antennas: &Antennas, max_size: (usize, usize)) -> usize {
+    let mut distinct_antinodes = HashSet::new();
+    for v in antennas.values() {
+        let antinodes = add_antinodes(v, max_size);
+        for val in antinodes {
+            distinct_antinodes.insert(val);
+        }
+    }
+    distinct_antinodes.len()
+}
+
+pub fn part_two(antennas: &Antennas, max_size: (usize, usize)) -> usize {
+    let mut distinct_antinodes = HashSet::new();
+    for v in antennas.values() {
+        let antinodes = add_recurring_antinodes(v, max_size);
+        for val in antinodes {
+            distinct_antinodes.insert(val);
+        }
+
+        for location in v {
+            distinct_antinodes.insert(*location);
+        }
+    }
+    distinct_antinodes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const INPUT: &str = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+
+    #[test]
+    fn test_part_one() {
+        let (antennas, max_size) = parse_input(INPUT);
+        let part_one = part_one(&antennas, max_size);
+        assert_eq!(part_one, 14);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let (antennas, max_size) = parse_input(INPUT);
+        let part_one = part_two(&antennas, max_size);
+        assert_eq!(part_one, 34);
+    }
+}
\ No newline at end of file